@@ -18,14 +18,10 @@
 mod multisig_factory {
 
     // Import the necessary dependencies.
-    use ink::codegen::EmitEvent;
     use ink::prelude::vec::Vec;
     use ink::ToAccountId;
     use multisig::{MultiSigRef, MultisigError};
 
-    /// The type encapsulating the events emitted by this contract.
-    type Event = <MultiSigFactory as ink::reflect::ContractEventBase>::Type;
-
     /// NewMultisig event emitted when a new MultiSig contract is deployed.
     #[ink(event)]
     pub struct NewMultisig {
@@ -88,15 +84,12 @@ mod multisig_factory {
                     // Success
                     Ok(multisig) => {
                         let multisig_address = multisig.to_account_id();
-                        Self::emit_event(
-                            Self::env(),
-                            Event::NewMultisig(NewMultisig {
-                                multisig_address,
-                                threshold,
-                                owners_list,
-                                salt,
-                            }),
-                        );
+                        Self::env().emit_event(NewMultisig {
+                            multisig_address,
+                            threshold,
+                            owners_list,
+                            salt,
+                        });
                         Ok(())
                     }
                     // Error
@@ -104,19 +97,5 @@ mod multisig_factory {
                 },
             }
         }
-
-        // We need this helper method for emitting events (rather than
-        // `Self::env().emit_event(_)`) because compiler will fail to
-        // resolve type boundaries if there are events from another, dependent
-        // contract. To verify, try replacing calls to
-        // `Self::emit_event` with `self::env().emit_event(_)` in the
-        // `../lib.rs`.
-        // This was taken from: https://github.com/Cardinal-Cryptography/bulletin-board-example/blob/main/contracts/highlighted_posts/lib.rs
-        fn emit_event<EE>(emitter: EE, event: Event)
-        where
-            EE: EmitEvent<MultiSigFactory>,
-        {
-            emitter.emit_event(event);
-        }
     }
 }
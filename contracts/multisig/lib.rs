@@ -22,16 +22,14 @@ mod multisig {
     // Import the necessary dependencies
     use ink::LangError;
     use ink::{
-        codegen::EmitEvent,
         env::{
-            call::{build_call, ExecutionInput},
-            CallFlags, Error as EnvError,
+            call::{build_call, ExecutionInput, FromAccountId},
+            CallFlags, Error as EnvError, ReturnFlags,
         },
-        prelude::{format, string::String, vec::Vec},
-        storage::Mapping,
+        prelude::{format, string::String, vec, vec::Vec},
+        storage::{traits::StorageKey, Mapping},
     };
-    use openbrush::traits::Flush;
-    use scale::Output;
+    use scale::{Input, Output};
 
     // Defined the types used in the contract
     /// TxId is the type used to identify a transaction in the contract
@@ -40,27 +38,140 @@ mod multisig {
     type Approvals = u8;
     /// Rejections is the type used to count the number of rejections for a transaction
     type Rejections = u8;
+    /// PolicyKey is the type used to identify a threshold policy by contract address and selector
+    type PolicyKey = (AccountId, [u8; 4]);
+    /// OwnerSlot is the type used to identify an owner in the voters bitmaps
+    type OwnerSlot = u8;
+    /// Voters is the type used to store the owners that voted a transaction as a bitmap indexed by owner slot
+    type Voters = u32;
 
     /// Define the constants used in the contract this constants may change depending
     /// on the kind of usage of the contract
     /// MAX_OWNERS is the maximum number of owners that can be added to the contract
     /// MAX_TRANSACTIONS is the maximum number of transactions that can be active at the same time
+    /// MAX_MODULES is the maximum number of modules that can be enabled at the same time
     const MAX_OWNERS: u8 = 10;
     const MAX_TRANSACTIONS: u8 = 10;
+    const MAX_MODULES: u8 = 10;
+    /// MAX_POLICY_RANGES is the maximum number of value ranges of a threshold policy
+    const MAX_POLICY_RANGES: u8 = 10;
+    /// MAX_POLICIES is the maximum number of threshold policies that can be set at the same time
+    const MAX_POLICIES: u8 = 10;
+    /// MAX_ALLOWED_DESTINATIONS is the maximum number of destinations in the allowlist
+    const MAX_ALLOWED_DESTINATIONS: u8 = 20;
+    /// MAX_DENIED_SELECTORS is the maximum number of selectors in the denylist
+    const MAX_DENIED_SELECTORS: u8 = 20;
+    /// MAX_DELEGATE_CODE_HASHES is the maximum number of code hashes allowed to be delegate called
+    const MAX_DELEGATE_CODE_HASHES: u8 = 10;
+    /// MAX_OWNER_SLOTS is the number of owner slots that fit in the voters bitmap, it cannot be lower than MAX_OWNERS
+    const MAX_OWNER_SLOTS: OwnerSlot = Voters::BITS as OwnerSlot;
+    /// MAX_GUARDIANS is the maximum number of guardians of the social recovery
+    const MAX_GUARDIANS: u8 = 10;
+    /// MIN_RECOVERY_DELAY is the minimum time in milliseconds the owners have to cancel a recovery (1 day)
+    const MIN_RECOVERY_DELAY: Timestamp = 86_400_000;
+    /// RECOVERY_PROPOSAL_PERIOD is the time in milliseconds the guardians have to reach the quorum of a recovery (7 days)
+    const RECOVERY_PROPOSAL_PERIOD: Timestamp = 604_800_000;
+    /// DEFAULT_PAUSE_DURATION is the number of blocks a pause lasts unless the owners change it (1 day of 6 seconds blocks)
+    const DEFAULT_PAUSE_DURATION: BlockNumber = 14_400;
+    /// MAX_TXS_PAGE is the maximum number of active transaction ids returned by a paginated query
+    const MAX_TXS_PAGE: u32 = 50;
+    /// MAX_STAKING_RECORDS_PAGE is the maximum number of staking records returned by a query
+    const MAX_STAKING_RECORDS_PAGE: u32 = 50;
+
+    /// Version of the storage layout of the contract. Delegate call scripts must be written
+    /// against this layout and report it, otherwise they could corrupt the multisig storage
+    const STORAGE_LAYOUT_VERSION: u32 = 8;
+
+    /// Selectors of the messages that a guard contract must implement
+    /// check_transaction(tx_id: TxId, tx: Transaction) -> bool is called before the transaction is performed
+    /// check_after_execution(tx_id: TxId, success: bool) -> bool is called after the transaction is performed
+    const GUARD_CHECK_TRANSACTION_SELECTOR: [u8; 4] = ink::selector_bytes!("check_transaction");
+    const GUARD_CHECK_AFTER_EXECUTION_SELECTOR: [u8; 4] =
+        ink::selector_bytes!("check_after_execution");
+    /// Selector of the perform_guarded_tx message, which performs a transaction checked by the guard
+    /// in a nested call of the multisig to itself
+    const PERFORM_GUARDED_TX_SELECTOR: [u8; 4] = ink::selector_bytes!("perform_guarded_tx");
+    /// Selector of the set_guard message. Transactions calling it are never checked by the guard,
+    /// so a faulty guard can always be replaced or removed
+    const SET_GUARD_SELECTOR: [u8; 4] = ink::selector_bytes!("set_guard");
+
+    /// Selector of the message that a delegate call script must implement
+    /// storage_layout_version() -> u32 returns the storage layout version the script was written against
+    const SCRIPT_STORAGE_LAYOUT_VERSION_SELECTOR: [u8; 4] =
+        ink::selector_bytes!("storage_layout_version");
+    /// Selectors of the self calls that can be proposed and executed while the wallet is paused,
+    /// so the owners can always unpause it and evict a compromised owner or pause guardian
+    const PAUSE_EXEMPT_SELECTORS: [[u8; 4]; 5] = [
+        ink::selector_bytes!("unpause"),
+        ink::selector_bytes!("remove_owner"),
+        ink::selector_bytes!("swap_owner"),
+        ink::selector_bytes!("set_config"),
+        ink::selector_bytes!("set_pause_guardian"),
+    ];
+    /// Selectors of the messages called on an outer multisig this multisig is an owner of
+    const PROPOSE_TX_SELECTOR: [u8; 4] = ink::selector_bytes!("propose_tx");
+    const APPROVE_TX_SELECTOR: [u8; 4] = ink::selector_bytes!("approve_tx");
+    const REJECT_TX_SELECTOR: [u8; 4] = ink::selector_bytes!("reject_tx");
+    const GET_TXS_AWAITING_VOTE_SELECTOR: [u8; 4] = ink::selector_bytes!("get_txs_awaiting_vote");
+    /// Selector of the transfer message. Transfers are not considered admin self calls
+    const TRANSFER_SELECTOR: [u8; 4] = ink::selector_bytes!("transfer");
+    /// Selectors of the staking helpers. They dispatch runtime calls, so the allowlist treats
+    /// them as runtime calls
+    const STAKING_SELECTORS: [[u8; 4]; 6] = [
+        ink::selector_bytes!("bond"),
+        ink::selector_bytes!("nominate"),
+        ink::selector_bytes!("unbond"),
+        ink::selector_bytes!("withdraw_unbonded"),
+        ink::selector_bytes!("join_pool"),
+        ink::selector_bytes!("claim_pool_payout"),
+    ];
 
     /// Struct to SCALE encode the input of the call
+    #[cfg(not(test))]
     struct InputArgs<'a>(&'a [u8]);
 
     /// Implementation of the SCALE encoding for the InputArgs struct
+    #[cfg(not(test))]
     impl<'a> scale::Encode for InputArgs<'a> {
         fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
             dest.write(self.0);
         }
     }
 
-    /// Define the events that will be emitted by the contract to be distinguished from the
-    /// events defined in the factory contract
-    type Event = <MultiSig as ink::reflect::ContractEventBase>::Type;
+    /// Struct to SCALE decode the output of the call as raw bytes
+    #[derive(PartialEq)]
+    struct OutputBytes(Vec<u8>);
+
+    /// Implementation of the SCALE encoding for the OutputBytes struct
+    impl scale::Encode for OutputBytes {
+        fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(&self.0);
+        }
+    }
+
+    /// Implementation of the SCALE decoding for the OutputBytes struct
+    /// All the remaining bytes of the output are taken as they are
+    impl scale::Decode for OutputBytes {
+        fn decode<I: Input>(input: &mut I) -> Result<Self, scale::Error> {
+            let len = input.remaining_len()?.unwrap_or_default();
+            let mut bytes = vec![0; len];
+            input.read(&mut bytes)?;
+            Ok(Self(bytes))
+        }
+    }
+
+    /// Reference to a contract instantiated by a transaction, only its account id is known
+    struct InstantiatedContract(AccountId);
+
+    impl ink::env::ContractEnv for InstantiatedContract {
+        type Env = <MultiSig as ink::env::ContractEnv>::Env;
+    }
+
+    impl FromAccountId<<MultiSig as ink::env::ContractEnv>::Env> for InstantiatedContract {
+        fn from_account_id(account_id: AccountId) -> Self {
+            Self(account_id)
+        }
+    }
 
     /// Emitted when the threshold is changed
     #[ink(event)]
@@ -70,6 +181,118 @@ mod multisig {
         threshold: u8,
     }
 
+    /// Emitted when the threshold ratio is changed
+    #[ink(event)]
+    pub struct ThresholdRatioChanged {
+        /// The new threshold ratio, None if the threshold is a fixed number of approvals
+        threshold_ratio: Option<ThresholdRatio>,
+    }
+
+    /// Emitted when the guard is changed
+    #[ink(event)]
+    pub struct GuardChanged {
+        /// The new guard contract, None if the guard has been removed
+        #[ink(topic)]
+        guard: Option<AccountId>,
+    }
+
+    /// Emitted when a threshold policy is set
+    #[ink(event)]
+    pub struct ThresholdPolicySet {
+        /// Contract address the policy applies to
+        #[ink(topic)]
+        address: AccountId,
+        /// Selector the policy applies to
+        selector: [u8; 4],
+        /// Value ranges of the policy with their required approvals
+        ranges: Vec<ThresholdPolicyRange>,
+    }
+
+    /// Emitted when a threshold policy is removed
+    #[ink(event)]
+    pub struct ThresholdPolicyRemoved {
+        /// Contract address the policy applied to
+        #[ink(topic)]
+        address: AccountId,
+        /// Selector the policy applied to
+        selector: [u8; 4],
+    }
+
+    /// Emitted when the admin threshold is changed
+    #[ink(event)]
+    pub struct AdminThresholdChanged {
+        /// The new admin threshold, None if the threshold is used
+        #[ink(topic)]
+        admin_threshold: Option<u8>,
+    }
+
+    /// Emitted when the rejection threshold is changed
+    #[ink(event)]
+    pub struct RejectionThresholdChanged {
+        /// The new rejection threshold, None if transactions are only cancelled when they cannot be approved
+        #[ink(topic)]
+        rejection_threshold: Option<u8>,
+    }
+
+    /// Emitted when the destination allowlist is enabled or disabled
+    #[ink(event)]
+    pub struct DestinationAllowlistChanged {
+        /// Whether the allowlist is enforced
+        enabled: bool,
+    }
+
+    /// Emitted when a destination is added to the allowlist
+    #[ink(event)]
+    pub struct DestinationAllowed {
+        /// Destination's account id
+        #[ink(topic)]
+        destination: AccountId,
+    }
+
+    /// Emitted when a destination is removed from the allowlist
+    #[ink(event)]
+    pub struct DestinationDisallowed {
+        /// Destination's account id
+        #[ink(topic)]
+        destination: AccountId,
+    }
+
+    /// Emitted when a selector is added to the denylist
+    #[ink(event)]
+    pub struct SelectorDenied {
+        /// Denied selector
+        #[ink(topic)]
+        selector: [u8; 4],
+        /// Minimum threshold a transaction calling the selector must require
+        min_threshold: u8,
+    }
+
+    /// Emitted when a selector is removed from the denylist
+    #[ink(event)]
+    pub struct SelectorAllowed {
+        /// Allowed selector
+        #[ink(topic)]
+        selector: [u8; 4],
+    }
+
+    /// Emitted when a code hash is allowed to be delegate called
+    #[ink(event)]
+    pub struct DelegateCodeHashAllowed {
+        /// Allowed code hash
+        #[ink(topic)]
+        code_hash: Hash,
+        /// Storage layout version the code was written against
+        layout_version: u32,
+    }
+
+    /// Emitted when a code hash is not allowed to be delegate called anymore
+    #[ink(event)]
+    pub struct DelegateCodeHashDisallowed {
+        /// Disallowed code hash
+        #[ink(topic)]
+        code_hash: Hash,
+    }
+
     /// Emmited when an owner is added
     #[ink(event)]
     pub struct OwnerAdded {
@@ -86,6 +309,141 @@ mod multisig {
         owner: AccountId,
     }
 
+    /// Emitted when the owners and the threshold are changed at once
+    #[ink(event)]
+    pub struct ConfigChanged {
+        /// Added owners' account ids
+        owners_added: Vec<AccountId>,
+        /// Removed owners' account ids
+        owners_removed: Vec<AccountId>,
+        /// The new threshold
+        threshold: u8,
+    }
+
+    /// Emitted when the social recovery configuration is changed
+    #[ink(event)]
+    pub struct RecoveryConfigChanged {
+        /// The new configuration, None if the social recovery has been disabled
+        config: Option<RecoveryConfig>,
+    }
+
+    /// Emitted when a guardian proposes a recovery
+    #[ink(event)]
+    pub struct RecoveryProposed {
+        /// Guardian's account id
+        #[ink(topic)]
+        guardian: AccountId,
+        /// Proposed owners
+        owners: Vec<AccountId>,
+        /// Proposed threshold
+        threshold: u8,
+    }
+
+    /// Emitted when a guardian approves the pending recovery
+    #[ink(event)]
+    pub struct RecoveryApproved {
+        /// Guardian's account id
+        #[ink(topic)]
+        guardian: AccountId,
+    }
+
+    /// Emitted when a guardian rejects the pending recovery
+    #[ink(event)]
+    pub struct RecoveryRejected {
+        /// Guardian's account id
+        #[ink(topic)]
+        guardian: AccountId,
+    }
+
+    /// Emitted when the pending recovery reaches the quorum and its delay starts
+    #[ink(event)]
+    pub struct RecoveryQuorumReached {
+        /// Timestamp from which the recovery can be executed
+        executable_at: Timestamp,
+    }
+
+    /// Emitted when the pending recovery is cancelled
+    #[ink(event)]
+    pub struct RecoveryCancelled {}
+
+    /// Emitted when the pending recovery is executed
+    #[ink(event)]
+    pub struct RecoveryExecuted {
+        /// The new owners
+        owners: Vec<AccountId>,
+        /// The new threshold
+        threshold: u8,
+    }
+
+    /// Emitted when the inheritance configuration is changed
+    #[ink(event)]
+    pub struct InheritanceConfigChanged {
+        /// The new configuration, None if the inheritance has been disabled
+        config: Option<InheritanceConfig>,
+    }
+
+    /// Emitted when a beneficiary claims the inheritance
+    #[ink(event)]
+    pub struct InheritanceClaimed {
+        /// Beneficiary's account id
+        #[ink(topic)]
+        beneficiary: AccountId,
+        /// Action performed by the claim
+        action: InheritanceAction,
+    }
+
+    /// Emitted when the wallet is paused
+    #[ink(event)]
+    pub struct Paused {
+        /// Owner or pause guardian that paused the wallet
+        #[ink(topic)]
+        by: AccountId,
+        /// Block number at which the pause expires
+        until: BlockNumber,
+    }
+
+    /// Emitted when the wallet is unpaused
+    #[ink(event)]
+    pub struct Unpaused {}
+
+    /// Emitted when the pause guardian is changed
+    #[ink(event)]
+    pub struct PauseGuardianChanged {
+        /// The new pause guardian, None if the pause guardian has been removed
+        pause_guardian: Option<AccountId>,
+    }
+
+    /// Emitted when the pause duration is changed
+    #[ink(event)]
+    pub struct PauseDurationChanged {
+        /// The new pause duration in blocks
+        duration: BlockNumber,
+    }
+
+    /// Emitted when an owner delegates its votes
+    #[ink(event)]
+    pub struct VotesDelegated {
+        /// Owner's account id
+        #[ink(topic)]
+        owner: AccountId,
+        /// Delegate's account id
+        #[ink(topic)]
+        delegate: AccountId,
+        /// Block number at which the delegation expires
+        expires_at: BlockNumber,
+    }
+
+    /// Emitted when an owner revokes its delegation
+    #[ink(event)]
+    pub struct DelegationRevoked {
+        /// Owner's account id
+        #[ink(topic)]
+        owner: AccountId,
+        /// Delegate's account id
+        #[ink(topic)]
+        delegate: AccountId,
+    }
+
     /// Emmited when a transaction is proposed
     #[ink(event)]
     pub struct TransactionProposed {
@@ -101,10 +459,16 @@ mod multisig {
         input: Vec<u8>,
         /// Transferred value of the call
         transferred_value: Balance,
-        /// Gas limit of the call
-        gas_limit: u64,
+        /// Ref time limit of the call
+        ref_time_limit: u64,
         /// Allow reentry flag of the call
         allow_reentry: bool,
+        /// Proof size limit of the call
+        proof_size_limit: u64,
+        /// Storage deposit limit of the call
+        storage_deposit_limit: Option<Balance>,
+        /// Type of the call
+        call_type: CallType,
         /// Address of the transaction proposer
         proposer: AccountId,
     }
@@ -118,6 +482,8 @@ mod multisig {
         /// approver's account id
         #[ink(topic)]
         owner: AccountId,
+        /// Delegate that voted on behalf of the owner, None if the owner voted
+        delegate: Option<AccountId>,
     }
 
     /// Emmited when a transaction is rejected
@@ -129,6 +495,8 @@ mod multisig {
         /// rejecter's account id
         #[ink(topic)]
         owner: AccountId,
+        /// Delegate that voted on behalf of the owner, None if the owner voted
+        delegate: Option<AccountId>,
     }
 
     /// Emmited when a transaction is executed
@@ -157,6 +525,65 @@ mod multisig {
         tx_id: TxId,
     }
 
+    /// Emitted when a module is enabled
+    #[ink(event)]
+    pub struct ModuleEnabled {
+        /// Module's account id
+        #[ink(topic)]
+        module: AccountId,
+    }
+
+    /// Emitted when a module is disabled
+    #[ink(event)]
+    pub struct ModuleDisabled {
+        /// Module's account id
+        #[ink(topic)]
+        module: AccountId,
+    }
+
+    /// Emitted when a transaction is executed by a module
+    #[ink(event)]
+    pub struct ModuleTransactionExecuted {
+        /// Transaction id
+        #[ink(topic)]
+        tx_id: TxId,
+        /// Module's account id
+        #[ink(topic)]
+        module: AccountId,
+        /// Result of the transaction execution
+        result: TxResult,
+    }
+
+    /// Emitted when a contract is instantiated by a transaction
+    #[ink(event)]
+    pub struct ContractInstantiated {
+        /// Transaction id
+        #[ink(topic)]
+        tx_id: TxId,
+        /// Address of the instantiated contract
+        #[ink(topic)]
+        contract: AccountId,
+        /// Code hash of the instantiated contract
+        code_hash: Hash,
+    }
+
+    /// Emitted when the indices of the staking pallets are changed
+    #[ink(event)]
+    pub struct StakingPalletsChanged {
+        /// The new indices, None if the staking helpers are disabled
+        pallets: Option<StakingPallets>,
+    }
+
+    /// Emitted when a staking operation is performed
+    #[ink(event)]
+    pub struct StakingOperationPerformed {
+        /// Index of the staking record
+        #[ink(topic)]
+        index: u32,
+        /// Staking operation performed
+        operation: StakingOperation,
+    }
+
     /// Emmited when a transfer is performed
     #[ink(event)]
     pub struct Transfer {
@@ -168,18 +595,30 @@ mod multisig {
     }
 
     /// Transaction result information that has either a success or a failure
-    #[derive(scale::Encode, scale::Decode)]
+    #[derive(scale::Encode, scale::Decode, Clone)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum TxResult {
-        /// Transaction executed successfully with the given result
-        Success(()),
+        /// Transaction executed successfully with the given return bytes
+        Success(Vec<u8>),
         /// Transaction failed with the given error
         Failed(MultisigError),
+        /// Transaction instantiated a contract at the given address
+        Instantiated(AccountId),
     }
 
-    /// Error types that can be returned by the contract
+    /// Simulation of a transaction execution
     #[derive(scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TxSimulation {
+        /// Result the transaction execution would have
+        pub result: TxResult,
+        /// Gas consumed by the transaction execution
+        pub gas_consumed: u64,
+    }
+
+    /// Error types that can be returned by the contract
+    #[derive(scale::Encode, scale::Decode, Clone)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum MultisigError {
         /// Env error encountered when executing the transaction
         EnvExecutionFailed(String),
@@ -209,6 +648,78 @@ mod multisig {
         InvalidTxId,
         /// The transfer has failed
         TransferFailed,
+        /// The guard contract rejected the transaction
+        GuardRejected,
+        /// The guard must be a contract
+        GuardNotContract,
+        /// No more modules can be enabled
+        MaxModulesReached,
+        /// The module is already enabled
+        ModuleAlreadyEnabled,
+        /// The caller is not an enabled module
+        NotModule,
+        /// The threshold policy ranges are not valid
+        InvalidPolicyRanges,
+        /// The threshold policy does not exist
+        PolicyNotFound,
+        /// The transaction destination is not in the allowlist
+        DestinationNotAllowed,
+        /// The transaction selector is denied for the threshold it requires
+        SelectorDenied,
+        /// The code hash is not allowed to be delegate called
+        DelegateCallNotAllowed,
+        /// The code was written against another storage layout
+        IncompatibleStorageLayout,
+        /// The delegate call modified the multisig storage
+        StorageCorrupted,
+        /// All the owner slots are held by owners or by voters of active transactions
+        NoOwnerSlotAvailable,
+        /// The recovery guardians or quorum are not valid
+        InvalidRecoveryConfig,
+        /// The caller is not a recovery guardian
+        NotGuardian,
+        /// There is already a pending recovery
+        RecoveryAlreadyPending,
+        /// There is no pending recovery
+        NoPendingRecovery,
+        /// The recovery has not reached the quorum or its delay has not elapsed yet
+        RecoveryNotExecutable,
+        /// The beneficiaries, threshold or inactivity period of the inheritance are not valid
+        InvalidInheritanceConfig,
+        /// The caller is not a beneficiary of the inheritance
+        NotBeneficiary,
+        /// The inactivity period of the owners has not elapsed yet
+        OwnersStillActive,
+        /// The wallet is paused
+        Paused,
+        /// The pause duration cannot be zero
+        InvalidPauseDuration,
+        /// The delegate cannot be an owner and the expiry must be in the future
+        InvalidDelegation,
+        /// The delegate already votes on behalf of another owner
+        DelegateAlreadyAssigned,
+        /// The owner has not delegated its votes
+        NoDelegation,
+        /// The threshold ratio must be between 0 and 1 and its minimum threshold cannot be zero
+        InvalidThresholdRatio,
+        /// The indices of the staking pallets of the runtime have not been configured
+        StakingNotConfigured,
+        /// The maximum number of threshold policies has been reached
+        MaxPoliciesReached,
+        /// The maximum number of destinations in the allowlist has been reached
+        MaxAllowedDestinationsReached,
+        /// The maximum number of selectors in the denylist has been reached
+        MaxDeniedSelectorsReached,
+        /// The maximum number of code hashes allowed to be delegate called has been reached
+        MaxDelegateCodeHashesReached,
+        /// The constructor of the instantiated contract returned an error, with its encoding
+        ConstructorFailed(Vec<u8>),
+        /// The recovery has not reached the quorum within RECOVERY_PROPOSAL_PERIOD
+        RecoveryExpired,
+        /// The recovery has reached the quorum, so only the owners can cancel it
+        RecoveryQuorumAlreadyReached,
+        /// The pause guardian cannot pause the wallet again until the cooldown after the last pause elapses
+        PauseCooldown,
     }
 
     impl From<EnvError> for MultisigError {
@@ -217,8 +728,36 @@ mod multisig {
         }
     }
 
+    /// Type of call performed by a transaction
+    #[derive(scale::Decode, scale::Encode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum CallType {
+        /// Regular call to the contract at the transaction address
+        Call,
+        /// Delegate call to the given code hash, which runs in the context of the multisig
+        /// The code hash must be allowed, and the transaction address is ignored
+        DelegateCall(Hash),
+        /// Instantiation of a contract from the given code hash and salt
+        /// The selector and input are the constructor ones, the transferred value is the
+        /// endowment, and the transaction address is ignored
+        Instantiate {
+            /// Code hash of the contract to be instantiated
+            code_hash: Hash,
+            /// Salt used to derive the contract address
+            salt: Vec<u8>,
+        },
+        /// Dispatch of a runtime call through call_runtime
+        /// The input is the SCALE encoded RuntimeCall, and the transaction address and selector are ignored
+        RuntimeCall,
+    }
+
     /// Structure that represents a transaction to be performed when the threshold is reached
-    #[derive(scale::Decode, scale::Encode)]
+    /// The Weight v2 limits and the call type are appended after the original fields, so
+    /// transactions encoded before their introduction can still be decoded (see the Decode implementation)
+    #[derive(scale::Encode)]
     #[cfg_attr(
         feature = "std",
         derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
@@ -232,187 +771,789 @@ mod multisig {
         pub input: Vec<u8>,
         /// Transferred value of the call
         pub transferred_value: Balance,
-        /// Gas limit of the call
-        pub gas_limit: u64,
+        /// Ref time limit of the call (formerly the gas limit), 0 means no limit
+        pub ref_time_limit: u64,
         /// Allow reentry flag of the call
         pub allow_reentry: bool,
+        /// Proof size limit of the call, 0 means no limit
+        pub proof_size_limit: u64,
+        /// Storage deposit limit of the call, None means no limit
+        pub storage_deposit_limit: Option<Balance>,
+        /// Type of the call
+        pub call_type: CallType,
     }
 
-    /// Structure that represents the multisig contract
-    /// It contains the list of owners, the threshold, the list of transactions and the list of approvals
-    /// The presence of redundant information between owners_list and owners, and transactions_id_list and transactions
-    /// is intentional to make it easier the elements access.
-    /// Although they represent the same TxId, this redundancy is maintained in order to support efficient iteration over
-    /// 'transactions_id_list' while fetching a transaction. By duplicating the tx IDs, we achieve a constant time complexity of
-    /// O(1) when accessing tx information directly from 'transacctions'.
-    #[ink(storage)]
-    #[derive(Default)]
-    pub struct MultiSig {
-        /// List of owners of the multisig contract
-        /// Owners are account ids that can propose, approve or reject transactions
-        owners_list: Vec<AccountId>,
-        /// Mapping of owners to check if an account id is an owner
-        owners: Mapping<AccountId, ()>,
-        /// Threshold of approvals required to execute a transaction
-        threshold: u8,
-        /// Next transaction id to be used (just a counter)
-        next_tx_id: TxId,
-        /// List of transactions that have been proposed
-        txs_id_list: Vec<TxId>,
-        /// Mapping of transactions to fetch a transaction by its id
-        txs: Mapping<TxId, Transaction>,
-        /// Mapping of approvals to check which owner has approved or rejected a transaction
-        approvals: Mapping<(TxId, AccountId), bool>,
-        /// Mapping of approvals count to check how many approvals a transaction has
-        approvals_count: Mapping<TxId, Approvals>,
-        /// Mapping of rejections count to check how many rejections a transaction has
-        rejections_count: Mapping<TxId, Rejections>,
-    }
-
-    impl MultiSig {
-        /// Constructor that creates a multisig contract with a list of owners and a threshold
-        /// The threshold is the minimum number of approvals required to execute a transaction
-        /// All the representation invariant checks are performed in the constructor
-        /// The list of owners is a list of account ids that can propose, approve or reject transactions
-        /// The list of owners cannot be empty
-        /// The owners cannot be duplicated
-        /// The threshold cannot be greater than the number of owners
-        /// The threshold cannot be zero
-        /// The maximum number of owners is defined by MAX_OWNERS
-        /// The maximum number of transactions is defined by MAX_TRANSACTIONS
-        /// The transaction Id is a counter that starts at 0 and is incremented by 1 for each transaction
-        /// The transaction Id cannot overflow
-        #[ink(constructor)]
-        pub fn new(threshold: u8, mut owners_list: Vec<AccountId>) -> Result<Self, MultisigError> {
-            // Remove duplicated owners
-            owners_list.sort_unstable();
-            owners_list.dedup();
-
-            // Check that the threshold and owners are valid
-            ensure_creation_params(threshold, &owners_list)?;
+    /// Implementation of the SCALE decoding for the Transaction struct
+    /// Transactions encoded before the Weight v2 limits were added end right after
+    /// allow_reentry, and the ones encoded before the call type was added end right after
+    /// storage_deposit_limit. When there are no bytes left the limits default to no limit
+    /// and the call type defaults to a regular call.
+    /// Because of this, a Transaction must always be the last value of the input it is decoded from
+    impl scale::Decode for Transaction {
+        fn decode<I: Input>(input: &mut I) -> Result<Self, scale::Error> {
+            let address = AccountId::decode(input)?;
+            let selector = <[u8; 4]>::decode(input)?;
+            let tx_input = Vec::<u8>::decode(input)?;
+            let transferred_value = Balance::decode(input)?;
+            let ref_time_limit = u64::decode(input)?;
+            let allow_reentry = bool::decode(input)?;
 
-            let mut owners = Mapping::new();
+            let (proof_size_limit, storage_deposit_limit) = match input.remaining_len()? {
+                Some(0) => (0, None),
+                _ => (u64::decode(input)?, Option::<Balance>::decode(input)?),
+            };
 
-            for owner in &owners_list {
-                owners.insert(owner, &());
-            }
+            let call_type = match input.remaining_len()? {
+                Some(0) => CallType::Call,
+                _ => CallType::decode(input)?,
+            };
 
             Ok(Self {
-                owners_list,
-                owners,
-                threshold,
-                next_tx_id: 0,
-                txs_id_list: Vec::new(),
-                txs: Mapping::new(),
-                approvals: Mapping::new(),
-                approvals_count: Mapping::new(),
-                rejections_count: Mapping::new(),
+                address,
+                selector,
+                input: tx_input,
+                transferred_value,
+                ref_time_limit,
+                allow_reentry,
+                proof_size_limit,
+                storage_deposit_limit,
+                call_type,
             })
         }
+    }
 
-        /// Transaction proposal
-        /// The parameters of the transaction are passed as a Transaction struct
-        /// The caller of this function must be an owner
-        /// The maximum number of transactions cannot be passed
-        /// The transaction Id cannot overflow
-        /// The transaction is stored in the contract
-        /// The transaction is initialized with 1 approval and 0 rejections
-        /// Emit TransactionProposed event
-        #[ink(message)]
-        pub fn propose_tx(&mut self, tx: Transaction) -> Result<(), MultisigError> {
-            // Check that the caller is an owner
-            self.ensure_is_owner(self.env().caller())?;
-
-            // Check that the maximum number of transactions has not been reached
-            if self.txs_id_list.len() as u8 == MAX_TRANSACTIONS {
-                return Err(MultisigError::MaxTransactionsReached);
-            }
+    /// Structure that represents the approvals required for the transactions whose
+    /// transferred value is within the range
+    #[derive(scale::Decode, scale::Encode, Clone)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ThresholdPolicyRange {
+        /// Minimum transferred value of the range (inclusive)
+        pub min_value: Balance,
+        /// Maximum transferred value of the range (inclusive), None if there is no upper bound
+        pub max_value: Option<Balance>,
+        /// Approvals required to execute the transactions within the range
+        pub threshold: u8,
+    }
 
-            // Handle next_tx_id
-            let current_tx_id = self.next_tx_id;
-            self.next_tx_id = current_tx_id
-                .checked_add(1)
-                .ok_or(MultisigError::TxIdOverflow)?;
+    /// Rounding of the threshold computed from a ratio of the owners
+    #[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ThresholdRounding {
+        /// Round up, 2/3 of 4 owners is 3
+        Up,
+        /// Round down, 2/3 of 4 owners is 2
+        Down,
+        /// Round to the nearest number of approvals, halves are rounded up
+        Nearest,
+    }
 
-            // Store the transaction
-            self.txs_id_list.push(current_tx_id);
-            // ink_storage::lazy::mapping::Mapping receives a reference, so we are passing a &transaction
-            self.txs.insert(current_tx_id, &tx);
+    /// Threshold expressed as a ratio of the owners, recomputed every time the owners change
+    #[derive(scale::Decode, scale::Encode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ThresholdRatio {
+        /// Numerator of the ratio, it cannot be greater than the denominator
+        pub numerator: u8,
+        /// Denominator of the ratio, it cannot be zero
+        pub denominator: u8,
+        /// Rounding of the owners ratio
+        pub rounding: ThresholdRounding,
+        /// Minimum threshold regardless of the number of owners, it cannot be zero
+        pub min_threshold: u8,
+    }
 
-            // Initialize the approvals count with 1 approval and 0 rejections
-            self.approvals_count.insert(current_tx_id, &1);
-            self.rejections_count.insert(current_tx_id, &0);
+    impl ThresholdRatio {
+        fn threshold(&self, owners_count: usize) -> u8 {
+            let owners_ratio = (owners_count as u32).saturating_mul(self.numerator as u32);
+            let denominator = self.denominator as u32;
+            // Offset the ratio so the division rounds down to the expected threshold
+            let rounded_ratio = match self.rounding {
+                ThresholdRounding::Up => owners_ratio.saturating_add(denominator.saturating_sub(1)),
+                ThresholdRounding::Down => owners_ratio,
+                ThresholdRounding::Nearest => owners_ratio.saturating_add(denominator / 2),
+            };
+            // The denominator is checked to be non-zero when the ratio is set
+            let threshold = rounded_ratio.checked_div(denominator).unwrap_or_default();
+            // The ratio is at most 1, so the threshold is never greater than the owners count
+            (threshold as u8).max(self.min_threshold)
+        }
+    }
 
-            self.approvals
-                .insert((current_tx_id, self.env().caller()), &true);
+    impl ThresholdPolicyRange {
+        fn contains(&self, value: Balance) -> bool {
+            value >= self.min_value && !matches!(self.max_value, Some(max) if value > max)
+        }
+    }
 
-            Self::emit_event(
-                Self::env(),
-                Event::TransactionProposed(TransactionProposed {
-                    tx_id: current_tx_id,
-                    contract_address: tx.address,
-                    selector: tx.selector,
-                    input: tx.input,
-                    transferred_value: tx.transferred_value,
-                    gas_limit: tx.gas_limit,
-                    allow_reentry: tx.allow_reentry,
-                    proposer: self.env().caller(),
-                }),
-            );
+    /// Votes of a transaction, packed in a single storage item along with its proposer
+    /// The approvals and rejections are bitmaps indexed by owner slot, and the counts are kept
+    /// alongside them. A proposal stores this 46 bytes record instead of the approvals count,
+    /// the rejections count and one (TxId, AccountId) entry per vote, so each vote is a single
+    /// read and write of the same item and the storage deposit no longer grows with the voters
+    #[derive(scale::Decode, scale::Encode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct TxVotes {
+        /// Owner that proposed the transaction
+        pub proposer: AccountId,
+        /// Owner slots that approved the transaction
+        pub approvals: Voters,
+        /// Owner slots that rejected the transaction
+        pub rejections: Voters,
+        /// Owner slots whose vote was cast by a delegate
+        pub delegated: Voters,
+        /// Number of approvals of the transaction
+        pub approvals_count: Approvals,
+        /// Number of rejections of the transaction
+        pub rejections_count: Rejections,
+    }
 
-            // If threshold is reached when proposed (threshold == 1), execute the transaction
-            self._try_execute_tx(current_tx_id);
+    /// Status of a transaction with everything needed to render it
+    /// Transactions don't expire and have no timelock, they stay active until they are executed or cancelled
+    #[derive(scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TxStatus {
+        /// Transaction to be performed
+        pub tx: Transaction,
+        /// Owner that proposed the transaction
+        pub proposer: AccountId,
+        /// Number of approvals of the transaction
+        pub approvals: Approvals,
+        /// Number of rejections of the transaction
+        pub rejections: Rejections,
+        /// Accounts that voted the transaction with their vote, true for approvals and false for rejections
+        /// Voters that are not owners anymore are included
+        pub votes: Vec<(AccountId, bool)>,
+        /// Owners whose vote was cast by a delegate, with the delegate that cast it
+        pub delegated_votes: Vec<(AccountId, AccountId)>,
+        /// Approvals required to execute the transaction
+        pub threshold: u8,
+        /// Whether the transaction has the approvals required to be executed
+        pub executable: bool,
+    }
 
-            Ok(())
+    /// Configuration of the social recovery
+    #[derive(scale::Encode, scale::Decode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct RecoveryConfig {
+        /// Accounts that can propose and approve a replacement of the owners
+        pub guardians: Vec<AccountId>,
+        /// Guardian approvals required to start the recovery delay
+        pub quorum: u8,
+        /// Time in milliseconds the owners have to cancel a recovery once it reaches the quorum,
+        /// it cannot be lower than MIN_RECOVERY_DELAY
+        pub delay: Timestamp,
+    }
+
+    /// Replacement of the owners proposed by the guardians
+    #[derive(scale::Encode, scale::Decode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Recovery {
+        /// Owners that replace the current ones
+        pub owners: Vec<AccountId>,
+        /// Threshold that replaces the current one
+        pub threshold: u8,
+        /// Guardians that approved the recovery, the proposer included
+        pub approvals: Vec<AccountId>,
+        /// Guardians that rejected the recovery
+        pub rejections: Vec<AccountId>,
+        /// Timestamp from which the recovery can be executed, None until the quorum is reached
+        pub executable_at: Option<Timestamp>,
+        /// Timestamp from which the recovery expires if it has not reached the quorum
+        pub expires_at: Timestamp,
+    }
+
+    /// Action performed when the inheritance is claimed
+    #[derive(scale::Encode, scale::Decode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum InheritanceAction {
+        /// The beneficiaries replace the owners with the given threshold
+        TakeControl(u8),
+        /// The whole balance above the minimum balance is transferred to the given account,
+        /// which keeps the multisig account alive
+        Sweep(AccountId),
+    }
+
+    /// Configuration of the dead-man's switch inheritance
+    #[derive(scale::Encode, scale::Decode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct InheritanceConfig {
+        /// Accounts that can claim the inheritance
+        pub beneficiaries: Vec<AccountId>,
+        /// Blocks without owner activity after which the inheritance can be claimed
+        pub inactivity_period: BlockNumber,
+        /// Action performed when the inheritance is claimed
+        pub action: InheritanceAction,
+    }
+
+    /// Delegation of the votes of an owner
+    #[derive(scale::Encode, scale::Decode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Delegation {
+        /// Account that approves and rejects transactions on behalf of the owner
+        pub delegate: AccountId,
+        /// Block number from which the delegate cannot vote anymore
+        pub expires_at: BlockNumber,
+    }
+
+    /// Summary of a transaction awaiting the vote of an owner
+    #[derive(scale::Encode, scale::Decode, Clone, PartialEq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TxSummary {
+        /// Transaction id
+        pub tx_id: TxId,
+        /// Contract address of the call
+        pub address: AccountId,
+        /// Selector of the call
+        pub selector: [u8; 4],
+        /// Transferred value of the call
+        pub transferred_value: Balance,
+        /// Owner that proposed the transaction
+        pub proposer: AccountId,
+        /// Number of approvals of the transaction
+        pub approvals: Approvals,
+        /// Number of rejections of the transaction
+        pub rejections: Rejections,
+        /// Approvals required to execute the transaction
+        pub threshold: u8,
+    }
+
+    /// Filters of the pending transactions query, None fields match every transaction
+    #[derive(scale::Encode, scale::Decode, Clone, Default)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct PendingTxsFilter {
+        /// Owner that proposed the transactions
+        pub proposer: Option<AccountId>,
+        /// Contract address called by the transactions, only regular calls have a target address
+        pub target: Option<AccountId>,
+        /// Owner that has not voted the transactions yet
+        pub awaiting_vote_of: Option<AccountId>,
+    }
+
+    impl TxVotes {
+        fn new(proposer: AccountId) -> Self {
+            TxVotes {
+                proposer,
+                approvals: 0,
+                rejections: 0,
+                delegated: 0,
+                approvals_count: 0,
+                rejections_count: 0,
+            }
         }
 
-        /// Transaction approval
+        fn voters(&self) -> Voters {
+            self.approvals | self.rejections
+        }
+
+        fn vote(&self, slot: OwnerSlot) -> Option<bool> {
+            if self.approvals & (1 << slot) != 0 {
+                Some(true)
+            } else if self.rejections & (1 << slot) != 0 {
+                Some(false)
+            } else {
+                None
+            }
+        }
+
+        fn is_delegated(&self, slot: OwnerSlot) -> bool {
+            self.delegated & (1 << slot) != 0
+        }
+
+        fn record(&mut self, slot: OwnerSlot, approval: bool) {
+            if approval {
+                self.approvals |= 1 << slot;
+                self.approvals_count = self.approvals_count.saturating_add(1);
+            } else {
+                self.rejections |= 1 << slot;
+                self.rejections_count = self.rejections_count.saturating_add(1);
+            }
+        }
+    }
+
+    /// Destination of the staking rewards
+    #[derive(scale::Decode, scale::Encode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum RewardDestination {
+        /// Rewards are paid into the stash account and added to the bonded amount
+        Staked,
+        /// Rewards are paid into the stash account without being bonded
+        Stash,
+        /// Rewards are paid into the controller account
+        Controller,
+        /// Rewards are paid into the given account
+        Account(AccountId),
+        /// Rewards are not paid
+        None,
+    }
+
+    /// Staking operation performed by the multisig through the staking helpers
+    #[derive(scale::Decode, scale::Encode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum StakingOperation {
+        /// Bond of the given value with the given rewards destination
+        Bond {
+            /// Bonded value
+            value: Balance,
+            /// Destination of the rewards
+            payee: RewardDestination,
+        },
+        /// Nomination of the given validators
+        Nominate {
+            /// Nominated validators
+            targets: Vec<AccountId>,
+        },
+        /// Unbond of the given value
+        Unbond {
+            /// Unbonded value
+            value: Balance,
+        },
+        /// Withdrawal of the unbonded funds whose unbonding period is over
+        WithdrawUnbonded {
+            /// Number of slashing spans of the stash
+            num_slashing_spans: u32,
+        },
+        /// Join of the given nomination pool with the given amount
+        JoinPool {
+            /// Bonded amount
+            amount: Balance,
+            /// Id of the nomination pool
+            pool_id: u32,
+        },
+        /// Claim of the pending nomination pool rewards
+        ClaimPoolPayout,
+    }
+
+    /// Record of a staking operation performed by the multisig
+    #[derive(scale::Decode, scale::Encode, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct StakingRecord {
+        /// Staking operation performed
+        pub operation: StakingOperation,
+        /// Block number in which the operation was performed
+        pub block_number: BlockNumber,
+        /// Timestamp of the block in which the operation was performed
+        pub timestamp: Timestamp,
+    }
+
+    /// Indices of the staking pallets in the runtime the contract is deployed on
+    /// They differ between runtimes, so they are configured by the owners
+    #[derive(scale::Decode, scale::Encode, Clone, Copy, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct StakingPallets {
+        /// Index of the staking pallet
+        pub staking: u8,
+        /// Index of the nomination pools pallet
+        pub nomination_pools: u8,
+    }
+
+    /// Runtime calls built by the staking helpers with the index of their pallet
+    /// The call indices are the ones of the substrate pallets
+    enum RuntimeCall {
+        Staking(u8, StakingCall),
+        NominationPools(u8, NominationPoolsCall),
+    }
+
+    /// Implementation of the SCALE encoding for the RuntimeCall enum
+    /// The pallet index is encoded as the variant index of the runtime call
+    impl scale::Encode for RuntimeCall {
+        fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+            match self {
+                RuntimeCall::Staking(pallet_index, call) => {
+                    dest.push_byte(*pallet_index);
+                    call.encode_to(dest);
+                }
+                RuntimeCall::NominationPools(pallet_index, call) => {
+                    dest.push_byte(*pallet_index);
+                    call.encode_to(dest);
+                }
+            }
+        }
+    }
+
+    /// Calls of the staking pallet
+    #[derive(scale::Encode)]
+    enum StakingCall {
+        #[codec(index = 0)]
+        Bond {
+            #[codec(compact)]
+            value: Balance,
+            payee: RewardDestination,
+        },
+        #[codec(index = 2)]
+        Unbond {
+            #[codec(compact)]
+            value: Balance,
+        },
+        #[codec(index = 3)]
+        WithdrawUnbonded { num_slashing_spans: u32 },
+        #[codec(index = 5)]
+        Nominate { targets: Vec<MultiAddress> },
+    }
+
+    /// Calls of the nomination pools pallet
+    #[derive(scale::Encode)]
+    enum NominationPoolsCall {
+        #[codec(index = 0)]
+        Join {
+            #[codec(compact)]
+            amount: Balance,
+            pool_id: u32,
+        },
+        #[codec(index = 2)]
+        ClaimPayout,
+    }
+
+    /// Address format used by the runtime calls, only account ids are supported
+    #[derive(scale::Encode)]
+    enum MultiAddress {
+        #[codec(index = 0)]
+        Id(AccountId),
+    }
+
+    impl StakingOperation {
+        /// Runtime call that performs the staking operation
+        fn runtime_call(&self, pallets: StakingPallets) -> RuntimeCall {
+            let staking = pallets.staking;
+            let nomination_pools = pallets.nomination_pools;
+            match self.clone() {
+                StakingOperation::Bond { value, payee } => {
+                    RuntimeCall::Staking(staking, StakingCall::Bond { value, payee })
+                }
+                StakingOperation::Nominate { targets } => RuntimeCall::Staking(
+                    staking,
+                    StakingCall::Nominate {
+                        targets: targets.into_iter().map(MultiAddress::Id).collect(),
+                    },
+                ),
+                StakingOperation::Unbond { value } => {
+                    RuntimeCall::Staking(staking, StakingCall::Unbond { value })
+                }
+                StakingOperation::WithdrawUnbonded { num_slashing_spans } => RuntimeCall::Staking(
+                    staking,
+                    StakingCall::WithdrawUnbonded { num_slashing_spans },
+                ),
+                StakingOperation::JoinPool { amount, pool_id } => RuntimeCall::NominationPools(
+                    nomination_pools,
+                    NominationPoolsCall::Join { amount, pool_id },
+                ),
+                StakingOperation::ClaimPoolPayout => {
+                    RuntimeCall::NominationPools(nomination_pools, NominationPoolsCall::ClaimPayout)
+                }
+            }
+        }
+    }
+
+    /// Structure that represents the multisig contract
+    /// It contains the list of owners, the threshold, the list of transactions and the list of approvals
+    /// The presence of redundant information between owners_list and owners, and active_txs and transactions
+    /// is intentional to make it easier the elements access.
+    /// Although they represent the same TxId, this redundancy is maintained in order to support efficient iteration over
+    /// 'active_txs' while fetching a transaction. By duplicating the tx IDs, we achieve a constant time complexity of
+    /// O(1) when accessing tx information directly from 'transacctions'.
+    /// Membership and authorization data (owners, modules, policies, allowlist, denylist and
+    /// delegate code hashes) is kept in the root, so a delegate call cannot add entries under
+    /// keys that the contract would never compare.
+    /// The active transactions are indexed by position, and a removal moves the last one to the freed position,
+    /// so proposing and removing a transaction are O(1) regardless of the number of active transactions.
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct MultiSig {
+        /// List of owners of the multisig contract
+        /// Owners are account ids that can propose, approve or reject transactions
+        owners_list: Vec<AccountId>,
+        /// Threshold of approvals required to execute a transaction
+        threshold: u8,
+        /// Ratio of the owners the threshold is computed from, None if the threshold is fixed
+        threshold_ratio: Option<ThresholdRatio>,
+        /// Next transaction id to be used (just a counter)
+        next_tx_id: TxId,
+        /// Mapping of the active transactions by their position, from 0 to active_txs_count
+        active_txs: Mapping<u32, TxId>,
+        /// Mapping of the active transactions to their position in active_txs
+        active_txs_positions: Mapping<TxId, u32>,
+        /// Number of active transactions
+        active_txs_count: u32,
+        /// Mapping of transactions to fetch a transaction by its id
+        txs: Mapping<TxId, Transaction>,
+        /// Mapping of votes to check which owners have approved or rejected a transaction and how many
+        /// The votes of a transaction are a single record indexed by owner slot
+        votes: Mapping<TxId, TxVotes>,
+        /// Mapping of owners, current and former, to their slot in the voters bitmaps
+        /// Former owners keep their slot while nobody else holds it
+        owner_slots: Mapping<AccountId, OwnerSlot>,
+        /// Mapping of slots to the owner that holds them
+        slot_owners: Mapping<OwnerSlot, AccountId>,
+        /// Optional guard contract that is asked to check every transaction execution
+        guard: Option<AccountId>,
+        /// Transaction being performed in a nested call checked by the guard, if any
+        guarded_tx: Option<TxId>,
+        /// List of enabled modules
+        /// Modules are contracts that can execute transactions without collecting approvals
+        modules_list: Vec<AccountId>,
+        /// List of threshold policies by contract address and selector
+        /// A policy overrides the threshold for the transactions it matches
+        policies: Vec<(PolicyKey, Vec<ThresholdPolicyRange>)>,
+        /// Threshold of approvals required by admin self calls, None to use the threshold
+        admin_threshold: Option<u8>,
+        /// Rejections that cancel a transaction, None to cancel it only when its threshold cannot be met
+        rejection_threshold: Option<u8>,
+        /// Whether proposals are only accepted for the destinations in the allowlist
+        destination_allowlist_enabled: bool,
+        /// List of destinations in the allowlist
+        allowed_destinations: Vec<AccountId>,
+        /// List of denied selectors with the minimum threshold a transaction calling them must require
+        denied_selectors: Vec<([u8; 4], u8)>,
+        /// List of the code hashes allowed to be delegate called with their storage layout version
+        delegate_code_hashes: Vec<(Hash, u32)>,
+        /// Indices of the staking pallets of the runtime, None until the owners configure them
+        staking_pallets: Option<StakingPallets>,
+        /// Mapping of the staking operations performed by the multisig by their index
+        staking_records: Mapping<u32, StakingRecord>,
+        /// Number of staking operations performed by the multisig
+        staking_records_count: u32,
+        /// Social recovery configuration, None if the social recovery is disabled
+        recovery_config: Option<RecoveryConfig>,
+        /// Recovery proposed by the guardians that has not been executed or cancelled yet
+        recovery: Option<Recovery>,
+        /// Inheritance configuration, None if the inheritance is disabled
+        inheritance_config: Option<InheritanceConfig>,
+        /// Block number of the last owner activity in the wallet
+        last_activity: BlockNumber,
+        /// List of owners with the block number of their last activity
+        /// It is kept in the root, so recording the activity never takes new storage cells
+        owners_last_activity: Vec<(AccountId, BlockNumber)>,
+        /// Account that can pause the wallet besides the owners, None if there is no pause guardian
+        pause_guardian: Option<AccountId>,
+        /// Number of blocks a pause lasts
+        pause_duration: BlockNumber,
+        /// Block number at which the last pause expires or was lifted, None if the wallet has never been paused
+        paused_until: Option<BlockNumber>,
+        /// Mapping of owners to the delegation of their votes
+        delegations: Mapping<AccountId, Delegation>,
+        /// Mapping of delegates to the owner they vote on behalf of
+        delegators: Mapping<AccountId, AccountId>,
+        /// Mapping of the votes cast by a delegate to the delegate, by transaction id and owner slot
+        vote_delegates: Mapping<(TxId, OwnerSlot), AccountId>,
+    }
+
+    impl MultiSig {
+        /// Constructor that creates a multisig contract with a list of owners and a threshold
+        /// The threshold is the minimum number of approvals required to execute a transaction
+        /// All the representation invariant checks are performed in the constructor
+        /// The list of owners is a list of account ids that can propose, approve or reject transactions
+        /// The list of owners cannot be empty
+        /// The owners cannot be duplicated
+        /// The threshold cannot be greater than the number of owners
+        /// The threshold cannot be zero
+        /// The maximum number of owners is defined by MAX_OWNERS
+        /// The maximum number of transactions is defined by MAX_TRANSACTIONS
+        /// The transaction Id is a counter that starts at 0 and is incremented by 1 for each transaction
+        /// The transaction Id cannot overflow
+        #[ink(constructor)]
+        pub fn new(threshold: u8, mut owners_list: Vec<AccountId>) -> Result<Self, MultisigError> {
+            // Remove duplicated owners
+            owners_list.sort_unstable();
+            owners_list.dedup();
+
+            // Check that the threshold and owners are valid
+            ensure_creation_params(threshold, &owners_list)?;
+
+            let mut owner_slots = Mapping::new();
+            let mut slot_owners = Mapping::new();
+
+            for (slot, owner) in owners_list.iter().enumerate() {
+                owner_slots.insert(owner, &(slot as OwnerSlot));
+                slot_owners.insert(slot as OwnerSlot, owner);
+            }
+
+            Ok(Self {
+                owners_list,
+                threshold,
+                threshold_ratio: None,
+                next_tx_id: 0,
+                active_txs: Mapping::new(),
+                active_txs_positions: Mapping::new(),
+                active_txs_count: 0,
+                txs: Mapping::new(),
+                votes: Mapping::new(),
+                owner_slots,
+                slot_owners,
+                guard: None,
+                guarded_tx: None,
+                modules_list: Vec::new(),
+                policies: Vec::new(),
+                admin_threshold: None,
+                rejection_threshold: None,
+                destination_allowlist_enabled: false,
+                allowed_destinations: Vec::new(),
+                denied_selectors: Vec::new(),
+                delegate_code_hashes: Vec::new(),
+                staking_pallets: None,
+                staking_records: Mapping::new(),
+                staking_records_count: 0,
+                recovery_config: None,
+                recovery: None,
+                inheritance_config: None,
+                last_activity: Self::env().block_number(),
+                owners_last_activity: Vec::new(),
+                pause_guardian: None,
+                pause_duration: DEFAULT_PAUSE_DURATION,
+                paused_until: None,
+                delegations: Mapping::new(),
+                delegators: Mapping::new(),
+                vote_delegates: Mapping::new(),
+            })
+        }
+
+        /// Transaction proposal
+        /// The parameters of the transaction are passed as a Transaction struct
         /// The caller of this function must be an owner
+        /// The destination must be in the allowlist if it is enabled
+        /// The code hash must be allowed if it is a delegate call
+        /// The selector cannot be denied unless the transaction requires the denylist minimum threshold
+        /// The wallet cannot be paused unless the transaction unpauses it
+        /// The maximum number of transactions cannot be passed
+        /// The transaction Id cannot overflow
+        /// The transaction is stored in the contract
+        /// The transaction is initialized with 1 approval and 0 rejections
+        /// Emit TransactionProposed event
+        #[ink(message)]
+        pub fn propose_tx(&mut self, tx: Transaction) -> Result<(), MultisigError> {
+            // Check that the caller is an owner
+            self.ensure_is_owner(self.env().caller())?;
+
+            // Record the activity of the owner
+            self.record_activity(self.env().caller());
+
+            // Check that the wallet is not paused
+            self.ensure_not_paused(&tx)?;
+
+            // Check that the destination and the selector are allowed
+            self.ensure_tx_allowed(&tx)?;
+
+            // Check that the maximum number of transactions has not been reached
+            if self.active_txs_count == MAX_TRANSACTIONS as u32 {
+                return Err(MultisigError::MaxTransactionsReached);
+            }
+
+            // Handle next_tx_id
+            let current_tx_id = self.next_tx_id;
+            self.next_tx_id = current_tx_id
+                .checked_add(1)
+                .ok_or(MultisigError::TxIdOverflow)?;
+
+            // Store the transaction
+            self.push_active_tx(current_tx_id);
+            // ink_storage::lazy::mapping::Mapping receives a reference, so we are passing a &transaction
+            self.txs.insert(current_tx_id, &tx);
+
+            // Initialize the votes with the approval of the proposer, replacing any stale record
+            self.votes
+                .insert(current_tx_id, &TxVotes::new(self.env().caller()));
+            self.record_vote(current_tx_id, self.env().caller(), None, true);
+
+            Self::env().emit_event(TransactionProposed {
+                tx_id: current_tx_id,
+                contract_address: tx.address,
+                selector: tx.selector,
+                input: tx.input,
+                transferred_value: tx.transferred_value,
+                ref_time_limit: tx.ref_time_limit,
+                allow_reentry: tx.allow_reentry,
+                proof_size_limit: tx.proof_size_limit,
+                storage_deposit_limit: tx.storage_deposit_limit,
+                call_type: tx.call_type,
+                proposer: self.env().caller(),
+            });
+
+            // If threshold is reached when proposed (threshold == 1), execute the transaction
+            self._try_execute_tx(current_tx_id)
+        }
+
+        /// Transaction approval
+        /// The caller of this function must be an owner or the delegate of an owner
+        /// A delegate votes on behalf of the owner until the delegation expires or is revoked
         /// The parameter of the transaction is the transaction Id
         /// The transaction Id must be valid
-        /// The caller must not have voted yet
+        /// The owner must not have voted yet
         /// The transaction is approved
         /// Emit Approve event
         /// The transaction is executed if the threshold is met
         #[ink(message)]
         pub fn approve_tx(&mut self, tx_id: TxId) -> Result<(), MultisigError> {
             // perform checks
-            self.perform_approval_rejection_checking(tx_id)?;
-            self.approve(tx_id)?;
+            let (owner, delegate) = self.perform_approval_rejection_checking(tx_id)?;
+            self.record_own_activity(owner, delegate);
+            self.approve(tx_id, owner, delegate)?;
 
-            Self::emit_event(
-                Self::env(),
-                Event::Approve(Approve {
-                    tx_id,
-                    owner: self.env().caller(),
-                }),
-            );
+            Self::env().emit_event(Approve {
+                tx_id,
+                owner,
+                delegate,
+            });
 
-            self._try_execute_tx(tx_id);
-            Ok(())
+            self._try_execute_tx(tx_id)
         }
 
         /// Transaction rejection
-        /// The caller of this function must be an owner
+        /// The caller of this function must be an owner or the delegate of an owner
+        /// A delegate votes on behalf of the owner until the delegation expires or is revoked
         /// The parameter of the transaction is the transaction Id
         /// The transaction Id must be valid
-        /// The caller must not have voted yet
+        /// The owner must not have voted yet
         /// The transaction is rejected
         /// Emit Reject event
         /// The transaction is removed if the threshold cannot be met with the remaining approvals
         #[ink(message)]
         pub fn reject_tx(&mut self, tx_id: TxId) -> Result<(), MultisigError> {
             // perform checks
-            self.perform_approval_rejection_checking(tx_id)?;
-            self.reject(tx_id)?;
+            let (owner, delegate) = self.perform_approval_rejection_checking(tx_id)?;
+            self.record_own_activity(owner, delegate);
+            self.reject(tx_id, owner, delegate)?;
 
-            Self::emit_event(
-                Self::env(),
-                Event::Reject(Reject {
-                    tx_id,
-                    owner: self.env().caller(),
-                }),
-            );
+            Self::env().emit_event(Reject {
+                tx_id,
+                owner,
+                delegate,
+            });
 
             self._try_remove_tx(tx_id);
             Ok(())
@@ -421,12 +1562,16 @@ mod multisig {
         /// Transaction execution
         /// The transaction Id must be valid
         /// The parameter of the transaction is the transaction Id
+        /// The wallet cannot be paused unless the transaction unpauses it
         /// The threshold must be met in order to execute the transaction
         #[ink(message)]
         pub fn try_execute_tx(&mut self, tx_id: TxId) -> Result<(), MultisigError> {
             self.is_tx_valid(tx_id)?;
-            self._try_execute_tx(tx_id);
-            Ok(())
+            let tx = self.txs.get(tx_id).expect(
+                "This should never fail. We are fetching a transaction that we know exists",
+            );
+            self.ensure_not_paused(&tx)?;
+            self._try_execute_tx(tx_id)
         }
 
         /// Transaction removal
@@ -440,6 +1585,113 @@ mod multisig {
             Ok(())
         }
 
+        /// Transaction execution by a module
+        /// The caller of this function must be an enabled module
+        /// The parameters of the transaction are passed as a Transaction struct
+        /// The destination must be in the allowlist if it is enabled
+        /// The code hash must be allowed if it is a delegate call
+        /// The selector cannot be denied, since the transaction collects no approvals
+        /// The transaction Id cannot overflow
+        /// The transaction is executed right away without collecting approvals
+        /// The guard, if any, checks the transaction as any other execution
+        /// Emit ModuleTransactionExecuted event
+        #[ink(message)]
+        pub fn exec_tx_from_module(&mut self, tx: Transaction) -> Result<TxResult, MultisigError> {
+            let module = self.env().caller();
+
+            // Check that the caller is an enabled module
+            self.ensure_is_module(module)?;
+
+            // Check that the wallet is not paused
+            self.ensure_not_paused(&tx)?;
+
+            // Check that the destination and the selector are allowed
+            self.ensure_tx_allowed(&tx)?;
+
+            // A denied selector requires a minimum number of approvals, which modules never collect
+            if self.get_denied_selector(tx.selector).is_some() {
+                return Err(MultisigError::SelectorDenied);
+            }
+
+            // Module transactions are not stored but they take a transaction id
+            // so they can be told apart from the proposed ones
+            let tx_id = self.next_tx_id;
+            self.next_tx_id = tx_id.checked_add(1).ok_or(MultisigError::TxIdOverflow)?;
+
+            let result = self.perform_tx(tx_id, &tx)?;
+
+            Self::env().emit_event(ModuleTransactionExecuted {
+                tx_id,
+                module,
+                result: result.clone(),
+            });
+
+            Ok(result)
+        }
+
+        /// Guarded transaction performance
+        /// The caller of this function must be the multisig contract itself
+        /// It is only callable while the multisig performs the transaction, in a nested call to itself
+        /// The transaction is performed and then checked by the guard
+        /// A rejection returns an error, so every change made by the transaction is reverted
+        #[ink(message)]
+        pub fn perform_guarded_tx(
+            &mut self,
+            tx_id: TxId,
+            tx: Transaction,
+        ) -> Result<TxResult, MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Check that the multisig is performing this transaction, so it can't be proposed
+            // to perform another one without collecting its approvals
+            if self.guarded_tx.take() != Some(tx_id) {
+                return Err(MultisigError::Unauthorized);
+            }
+            let guard = self.guard.ok_or(MultisigError::Unauthorized)?;
+
+            let result = self.perform_call(tx_id, &tx)?;
+
+            // If the guard rejects the outcome the nested call is reverted
+            let success = !matches!(result, TxResult::Failed(_));
+            if !guard_approves(guard_check_after_execution(guard, tx_id, success)) {
+                return Err(MultisigError::GuardRejected);
+            }
+
+            Ok(result)
+        }
+
+        // Simulation
+        /// Transaction simulation
+        /// The parameter of the transaction is the transaction Id
+        /// The transaction Id must be valid
+        /// The transaction is performed as it would be when executed, guard checks included,
+        /// regardless of its approvals
+        /// Returns the result the execution would have and the gas it would consume
+        /// Every change is always reverted, so this is meant to be used as a query
+        #[ink(message)]
+        pub fn simulate_tx(&mut self, tx_id: TxId) -> Result<TxSimulation, MultisigError> {
+            self.is_tx_valid(tx_id)?;
+            let tx = self
+                .get_tx(tx_id)
+                .expect("This should never fail because we are checking the tx_id before");
+            self.simulate(tx_id, &tx)
+        }
+
+        /// Candidate transaction simulation
+        /// The parameters of the transaction are passed as a Transaction struct
+        /// The transaction is performed as it would be when executed, guard checks included,
+        /// as if it was proposed with the next transaction Id
+        /// Returns the result the execution would have and the gas it would consume
+        /// Every change is always reverted, so this is meant to be used as a query
+        #[ink(message)]
+        pub fn simulate_transaction(
+            &mut self,
+            tx: Transaction,
+        ) -> Result<TxSimulation, MultisigError> {
+            self.simulate(self.next_tx_id, &tx)
+        }
+
         // Owner management
         /// Owner addition
         /// The caller of this function must be the multisig contract itself
@@ -447,7 +1699,9 @@ mod multisig {
         /// Perform checking representation invariants
         /// The maximum number of owners cannot be reached
         /// The owner cannot be already an owner
+        /// The owner gets its former slot back, or a slot that no active transaction voter holds
         /// The owner is added
+        /// The threshold is recomputed if it is a ratio of the owners
         /// Emit OwnerAdded event
         #[ink(message)]
         pub fn add_owner(&mut self, owner: AccountId) -> Result<(), MultisigError> {
@@ -460,16 +1714,22 @@ mod multisig {
             }
 
             // Check that owner is not already an owner
-            if self.owners.contains(owner) {
+            if self.owners_list.contains(&owner) {
                 return Err(MultisigError::OwnerAlreadyExists);
             }
 
-            // Add the owner
-            self.owners.insert(owner, &());
+            // Assign a slot to the owner for the voters bitmaps
+            self.assign_owner_slot(owner)?;
+
+            // Add the owner without any delegation left from a former ownership
+            self.remove_delegation(owner);
             self.owners_list.push(owner);
 
             // emit event
-            Self::emit_event(Self::env(), Event::OwnerAdded(OwnerAdded { owner }));
+            Self::env().emit_event(OwnerAdded { owner });
+
+            // Recompute the threshold for the new owners count
+            self.update_threshold(self.threshold_for(self.owners_list.len()));
 
             Ok(())
         }
@@ -480,6 +1740,7 @@ mod multisig {
         /// Perform checking representation invariants
         /// The owners cannot be empty after removing
         /// The threshold cannot be greater than the number of owners after removing
+        /// If the threshold is a ratio of the owners it is recomputed, only its minimum can block the removal
         /// The owner is removed
         /// Emit OwnerRemoved event
         #[ink(message)]
@@ -498,16 +1759,22 @@ mod multisig {
             }
 
             // Check that threshold is not greater than owners after removing
-            if self.threshold > (owners_count - 1) as u8 {
+            let remaining_owners = owners_count.saturating_sub(1);
+            let threshold = self.threshold_for(remaining_owners);
+            if threshold > remaining_owners as u8 {
                 return Err(MultisigError::ThresholdGreaterThanOwners);
             }
 
             // Remove the owner
-            self.owners.remove(owner);
+            self.owners_last_activity.retain(|(x, _)| *x != owner);
+            self.remove_delegation(owner);
             self.owners_list.retain(|&x| x != owner);
 
             // emit event
-            Self::emit_event(Self::env(), Event::OwnerRemoved(OwnerRemoved { owner }));
+            Self::env().emit_event(OwnerRemoved { owner });
+
+            // Apply the threshold for the new owners count
+            self.update_threshold(threshold);
 
             Ok(())
         }
@@ -518,7 +1785,7 @@ mod multisig {
         /// Perform checking representation invariants
         /// The threshold cannot be greater than the number of owners
         /// The threshold cannot be zero
-        /// The threshold is changed
+        /// The threshold is changed and it is not a ratio of the owners anymore
         /// Emit ThresholdChanged event
         #[ink(message)]
         pub fn change_threshold(&mut self, threshold: u8) -> Result<(), MultisigError> {
@@ -537,322 +1804,5192 @@ mod multisig {
 
             // Change the threshold
             self.threshold = threshold;
+            self.clear_threshold_ratio();
 
             // emit event
-            Self::emit_event(
-                Self::env(),
-                Event::ThresholdChanged(ThresholdChanged { threshold }),
-            );
+            Self::env().emit_event(ThresholdChanged { threshold });
 
             Ok(())
         }
 
-        /// Transfer funds from the contract to another account
+        /// Threshold ratio change
         /// The caller of this function must be the multisig contract itself
-        /// The parameter of the transaction is the receiver's account id and the amount to be transferred
-        /// The transfer is performed
-        /// Emit Transfer event
+        /// The parameter of the transaction is the ratio of the owners required to execute a transaction,
+        /// None to keep the current threshold as a fixed number of approvals
+        /// The ratio must be between 0 and 1 and its minimum threshold cannot be zero or greater than the
+        /// number of owners
+        /// The threshold is recomputed now and every time an owner is added or removed
+        /// Emit ThresholdRatioChanged event
         #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<(), MultisigError> {
+        pub fn set_threshold_ratio(
+            &mut self,
+            threshold_ratio: Option<ThresholdRatio>,
+        ) -> Result<(), MultisigError> {
             // Check that caller is multisig
             self.ensure_self_call()?;
 
-            // Transfer the funds
-            // Balance checks are being done inside the transfer function
-            self.env()
-                .transfer(to, value)
-                .map_err(|_| MultisigError::TransferFailed)?;
+            // Check that the ratio is valid
+            if let Some(threshold_ratio) = &threshold_ratio {
+                ensure_threshold_ratio(threshold_ratio, self.owners_list.len())?;
+            }
+
+            // Change the threshold ratio
+            self.threshold_ratio = threshold_ratio.clone();
 
             // emit event
-            Self::emit_event(Self::env(), Event::Transfer(Transfer { to, value }));
+            Self::env().emit_event(ThresholdRatioChanged { threshold_ratio });
+
+            // Apply the threshold of the ratio
+            self.update_threshold(self.threshold_for(self.owners_list.len()));
 
             Ok(())
         }
 
-        //-------------------------------------------------------
-        // Internal functions
-        //-------------------------------------------------------
+        /// Owner swap
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the owner to be replaced and the new owner
+        /// The owners count and the threshold are kept, so the swap cannot break the threshold invariants
+        /// A threshold ratio is kept too
+        /// The new owner cannot be already an owner
+        /// Emit ConfigChanged event
+        #[ink(message)]
+        pub fn swap_owner(
+            &mut self,
+            old_owner: AccountId,
+            new_owner: AccountId,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
 
-        fn ensure_self_call(&self) -> Result<(), MultisigError> {
-            if self.env().caller() != self.env().account_id() {
-                return Err(MultisigError::Unauthorized);
-            }
-            Ok(())
+            self.set_owners_and_threshold(vec![new_owner], vec![old_owner], self.threshold)
         }
 
-        fn ensure_is_owner(&self, owner: AccountId) -> Result<(), MultisigError> {
-            self.owners
-                .contains(owner)
-                .then_some(())
-                .ok_or(MultisigError::NotOwner)
+        /// Configuration change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the owners to add, the owners to remove and the new threshold
+        /// The owners are removed before the new ones are added
+        /// Only the final state is checked, with the same rules as the constructor params
+        /// The owners to remove must be owners and the owners to add cannot be already owners
+        /// The maximum number of owners cannot be passed
+        /// The threshold is not a ratio of the owners anymore
+        /// Emit ConfigChanged event
+        #[ink(message)]
+        pub fn set_config(
+            &mut self,
+            owners_to_add: Vec<AccountId>,
+            owners_to_remove: Vec<AccountId>,
+            threshold: u8,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.set_owners_and_threshold(owners_to_add, owners_to_remove, threshold)?;
+            self.clear_threshold_ratio();
+
+            Ok(())
         }
 
-        fn ensure_not_already_voted(&self, tx_id: TxId) -> Result<(), MultisigError> {
-            if self.approvals.get((tx_id, self.env().caller())).is_some() {
-                return Err(MultisigError::AlreadyVoted);
+        /// Guard change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the guard contract's account id, or None to remove it
+        /// The guard must be a contract
+        /// Every transaction execution is checked by the guard before and after being performed
+        /// A rejection before the call marks the transaction as failed without performing it
+        /// A rejection after the call reverts every change made by it and marks the transaction as failed
+        /// Transactions calling set_guard are never checked, so a faulty guard can always be replaced
+        /// Emit GuardChanged event
+        #[ink(message)]
+        pub fn set_guard(&mut self, guard: Option<AccountId>) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Check that the guard is a contract
+            if let Some(guard) = guard {
+                if !self.env().is_contract(&guard) {
+                    return Err(MultisigError::GuardNotContract);
+                }
             }
+
+            // Change the guard
+            self.guard = guard;
+
+            // emit event
+            Self::env().emit_event(GuardChanged { guard });
+
             Ok(())
         }
 
-        fn check_threshold_met(&self, tx_id: TxId) -> bool {
-            // Fetch the approvals for the transaction
-            let approvals = self.approvals_count.get(tx_id).expect("This should never fail. We are fetching the approvals count for a transaction that we know exists");
-            approvals >= self.threshold
-        }
+        // Module management
+        /// Module enabling
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the module's account id
+        /// The maximum number of modules cannot be reached
+        /// The module cannot be already enabled
+        /// The module is enabled
+        /// Emit ModuleEnabled event
+        #[ink(message)]
+        pub fn enable_module(&mut self, module: AccountId) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
 
-        fn check_threshold_can_be_met(&self, tx_id: TxId) -> bool {
-            // Fetch the rejections for the transaction
-            let rejections  = self.rejections_count.get(tx_id).expect("This should never fail. We are fetching the approvals count for a transaction that we know exists");
+            // Check that modules are not greater than MAX_MODULES
+            if self.modules_list.len() as u8 == MAX_MODULES {
+                return Err(MultisigError::MaxModulesReached);
+            }
 
-            // if the rejections are greater than owners - threshold, then the threshold can't be met
-            rejections <= self.owners_list.len() as u8 - self.threshold
+            // Check that module is not already enabled
+            if self.modules_list.contains(&module) {
+                return Err(MultisigError::ModuleAlreadyEnabled);
+            }
+
+            // Enable the module
+            self.modules_list.push(module);
+
+            // emit event
+            Self::env().emit_event(ModuleEnabled { module });
+
+            Ok(())
         }
 
-        fn perform_approval_rejection_checking(
-            &mut self,
-            tx_id: TxId,
-        ) -> Result<(), MultisigError> {
-            // Check that the caller is an owner
-            self.ensure_is_owner(self.env().caller())?;
+        /// Module disabling
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the module's account id
+        /// The module must be enabled
+        /// The module is disabled
+        /// Emit ModuleDisabled event
+        #[ink(message)]
+        pub fn disable_module(&mut self, module: AccountId) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
 
-            // Check that the transaction exists
-            self.is_tx_valid(tx_id)?;
+            // Check that module is actually enabled
+            self.ensure_is_module(module)?;
+
+            // Disable the module
+            self.modules_list.retain(|&x| x != module);
 
-            // Check that the caller has not voted yet
-            self.ensure_not_already_voted(tx_id)?;
+            // emit event
+            Self::env().emit_event(ModuleDisabled { module });
 
             Ok(())
         }
 
-        fn _try_execute_tx(&mut self, tx_id: TxId) {
-            // Save current changes to storage
-            self.flush();
+        // Threshold policies
+        /// Threshold policy setting
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the contract address, the selector and the value ranges
+        /// The ranges cannot be empty, more than MAX_POLICY_RANGES or overlap between them
+        /// The threshold of each range cannot be zero or greater than the number of owners
+        /// Transactions calling the selector on the address use the threshold of the range
+        /// containing their transferred value, or the default threshold if no range contains it
+        /// Emit ThresholdPolicySet event
+        #[ink(message)]
+        pub fn set_threshold_policy(
+            &mut self,
+            address: AccountId,
+            selector: [u8; 4],
+            ranges: Vec<ThresholdPolicyRange>,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
 
-            // check threshold met
-            if self.check_threshold_met(tx_id) {
-                // execute transaction
-                self.execute_tx(tx_id);
+            // Check that the ranges are valid
+            if ranges.is_empty() || ranges.len() > MAX_POLICY_RANGES as usize {
+                return Err(MultisigError::InvalidPolicyRanges);
             }
-        }
 
-        fn execute_tx(&mut self, tx_id: TxId) {
-            // Fetch the transaction
-            let tx = self.get_tx(tx_id).expect("This should never fail because we are checking the tx_id before calling this function");
+            for range in &ranges {
+                ensure_threshold(range.threshold, self.owners_list.len())?;
 
-            let tx_result = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
-                .call(tx.address)
-                .gas_limit(tx.gas_limit)
-                .transferred_value(tx.transferred_value)
-                .call_flags(CallFlags::default().set_allow_reentry(tx.allow_reentry))
-                .exec_input(ExecutionInput::new(tx.selector.into()).push_arg(InputArgs(&tx.input)))
-                .returns::<()>()
-                .try_invoke();
+                if matches!(range.max_value, Some(max) if max < range.min_value) {
+                    return Err(MultisigError::InvalidPolicyRanges);
+                }
+            }
 
-            // Instead of just returning a custom Error we could return the error from the call
-            let result = match tx_result {
-                Ok(Ok(bytes)) => TxResult::Success(bytes),
-                Ok(Err(e)) => TxResult::Failed(MultisigError::LangExecutionFailed(e)),
-                Err(e) => TxResult::Failed(MultisigError::from(e)),
-            };
+            // Check that the ranges do not overlap
+            let mut ranges = ranges;
+            ranges.sort_unstable_by_key(|range| range.min_value);
+            for pair in ranges.windows(2) {
+                if !matches!(pair[0].max_value, Some(max) if max < pair[1].min_value) {
+                    return Err(MultisigError::InvalidPolicyRanges);
+                }
+            }
 
-            // We need to load the storage again because the call might have changed it.
-            // In order to use this we imported the Flush trait from openbrush.
-            // Importing openbrush 3.1.0 forced us to downgrade ink to 4.1.0
-            // check if it is reentrant for the same contract to perform the loading again
-            if tx.allow_reentry && tx.address == self.env().account_id() {
-                self.load();
+            // Set the policy, replacing the previous one of the address and selector
+            let key = (address, selector);
+            match self.policies.iter().position(|(x, _)| *x == key) {
+                Some(position) => self.policies[position].1 = ranges.clone(),
+                None if self.policies.len() == MAX_POLICIES as usize => {
+                    return Err(MultisigError::MaxPoliciesReached)
+                }
+                None => self.policies.push((key, ranges.clone())),
             }
 
-            // Delete the transaction from the storage
-            self.remove_tx(tx_id);
+            // emit event
+            Self::env().emit_event(ThresholdPolicySet {
+                address,
+                selector,
+                ranges,
+            });
 
-            // Emit event
-            Self::emit_event(
-                Self::env(),
-                Event::TransactionExecuted(TransactionExecuted { tx_id, result }),
-            );
+            Ok(())
         }
 
-        fn _try_remove_tx(&mut self, tx_id: TxId) {
-            // check if threshold can be met with the remaining approvals
-            if !self.check_threshold_can_be_met(tx_id) {
-                Self::emit_event(
-                    Self::env(),
-                    Event::TransactionCancelled(TransactionCancelled { tx_id }),
-                );
+        /// Threshold policy removal
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the contract address and the selector
+        /// The policy must exist
+        /// Emit ThresholdPolicyRemoved event
+        #[ink(message)]
+        pub fn remove_threshold_policy(
+            &mut self,
+            address: AccountId,
+            selector: [u8; 4],
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
 
-                // delete transaction
-                self.remove_tx(tx_id);
+            // Check that the policy exists
+            if self.policy(address, selector).is_none() {
+                return Err(MultisigError::PolicyNotFound);
             }
-        }
 
-        fn remove_tx(&mut self, tx_id: TxId) {
-            // Remove the transaction from the index list
-            self.txs_id_list.retain(|&x| x != tx_id);
+            // Remove the policy
+            self.policies.retain(|(key, _)| *key != (address, selector));
 
-            // Remove the transaction from the mappping
-            self.txs.remove(tx_id);
+            // emit event
+            Self::env().emit_event(ThresholdPolicyRemoved { address, selector });
 
-            // Remove the transaction from the approvals count
-            self.approvals_count.remove(tx_id);
+            Ok(())
+        }
 
-            // Remove the transaction from the rejections count
-            self.rejections_count.remove(tx_id);
+        /// Admin threshold change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the new admin threshold, None to use the threshold
+        /// The admin threshold cannot be zero or greater than the number of owners
+        /// Admin self calls are all the calls to the multisig contract itself except transfers
+        /// By default they require the threshold, a stricter admin threshold has to be set explicitly
+        /// Emit AdminThresholdChanged event
+        #[ink(message)]
+        pub fn change_admin_threshold(
+            &mut self,
+            admin_threshold: Option<u8>,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
 
-            // Remove the approvals TODO: check if there is a more efficient way of doing it
-            for owner in self.owners_list.iter() {
-                self.approvals.remove((tx_id, *owner));
+            // Check that the admin threshold is valid
+            if let Some(admin_threshold) = admin_threshold {
+                ensure_threshold(admin_threshold, self.owners_list.len())?;
             }
 
+            // Change the admin threshold
+            self.admin_threshold = admin_threshold;
+
             // emit event
-            Self::emit_event(
-                Self::env(),
-                Event::TransactionRemoved(TransactionRemoved { tx_id }),
-            );
-        }
+            Self::env().emit_event(AdminThresholdChanged { admin_threshold });
 
-        fn approve(&mut self, tx_id: TxId) -> Result<(), MultisigError> {
-            let approvals = self
-                .approvals_count
-                .get(tx_id)
-                .expect("This cannot panic if checks already perfromed");
-            self.approvals_count.insert(tx_id, &(approvals + 1));
-            self.approvals.insert((tx_id, self.env().caller()), &true);
             Ok(())
         }
 
-        fn reject(&mut self, tx_id: TxId) -> Result<(), MultisigError> {
-            let rejections = self
-                .rejections_count
-                .get(tx_id)
-                .expect("This cannot panic if checks already perfromed");
-            self.rejections_count.insert(tx_id, &(rejections + 1));
-            self.approvals.insert((tx_id, self.env().caller()), &false);
-            Ok(())
-        }
-
-        // We need this helper method for emitting events (rather than
-        // `Self::env().emit_event(_)`) because compiler will fail to
-        // resolve type boundaries if there are events from another, dependent
-        // contract. To verify, try replacing calls to
-        // `Self::emit_event` with `self::env().emit_event(_)` in the
-        // `../lib.rs`.
-        // This was taken from: https://github.com/Cardinal-Cryptography/bulletin-board-example/blob/main/contracts/highlighted_posts/lib.rs
-        fn emit_event<EE>(emitter: EE, event: Event)
-        where
-            EE: EmitEvent<MultiSig>,
-        {
-            emitter.emit_event(event);
-        }
+        /// Rejection threshold change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the new rejection threshold, None to remove it
+        /// The rejection threshold cannot be zero or greater than the number of owners
+        /// A transaction is cancelled as soon as it reaches the rejection threshold, and in any case
+        /// when the rejections are greater than the number of owners minus its threshold
+        /// Emit RejectionThresholdChanged event
+        #[ink(message)]
+        pub fn change_rejection_threshold(
+            &mut self,
+            rejection_threshold: Option<u8>,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
 
-        //-------------------------------------------------------
-        // Read functions
-        //-------------------------------------------------------
+            // Check that the rejection threshold is valid
+            if let Some(rejection_threshold) = rejection_threshold {
+                ensure_threshold(rejection_threshold, self.owners_list.len())?;
+            }
 
-        /// Owners
-        /// Get Owners
-        /// The owners list is a list of account ids that can propose, approve or reject transactions
-        #[ink(message)]
-        pub fn get_owners(&self) -> Vec<AccountId> {
-            self.owners_list.clone()
-        }
+            // Change the rejection threshold
+            self.rejection_threshold = rejection_threshold;
 
-        /// Is owner
-        /// The parameter of the transaction is the owner's account id
-        /// The owner is checked if it is an owner
-        #[ink(message)]
-        pub fn is_owner(&self, owner: AccountId) -> bool {
-            self.owners.contains(owner)
-        }
+            // emit event
+            Self::env().emit_event(RejectionThresholdChanged {
+                rejection_threshold,
+            });
 
-        /// Treshold
-        /// Get Threshold
-        /// The threshold is the current minimum number of approvals required to execute a transaction
-        #[ink(message)]
-        pub fn get_threshold(&self) -> u8 {
-            self.threshold
+            Ok(())
         }
 
-        /// Transactions
-        /// Get Next Transaction Id
-        /// Returns the next transaction id
+        // Destination allowlist and selector denylist
+        /// Destination allowlist enabling or disabling
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is whether the allowlist is enforced
+        /// While enabled, transactions to destinations out of the allowlist are refused when they
+        /// are proposed or executed
+        /// The multisig contract itself is always an allowed destination, but the receiver of
+        /// a transfer must be in the allowlist
+        /// Instantiations, runtime calls and staking helpers are refused while it is enabled,
+        /// since they have no destination to check
+        /// Emit DestinationAllowlistChanged event
         #[ink(message)]
-        pub fn get_next_tx_id(&self) -> TxId {
-            self.next_tx_id
-        }
+        pub fn set_destination_allowlist_enabled(
+            &mut self,
+            enabled: bool,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
 
-        /// Get Active Transactions Id List
-        /// Returns the list of active transactions
-        #[ink(message)]
-        pub fn get_active_txid_list(&self) -> Vec<TxId> {
-            self.txs_id_list.clone()
+            // Enable or disable the allowlist
+            self.destination_allowlist_enabled = enabled;
+
+            // emit event
+            Self::env().emit_event(DestinationAllowlistChanged { enabled });
+
+            Ok(())
         }
 
-        /// Get Transaction
-        /// The parameter of the transaction is the transaction id
-        /// Returns the transaction or None if the transaction id is not valid
+        /// Destination allowance
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the destination's account id
+        /// The destination is added to the allowlist
+        /// Emit DestinationAllowed event
         #[ink(message)]
-        pub fn get_tx(&self, index: TxId) -> Option<Transaction> {
-            self.txs.get(index)
+        pub fn allow_destination(&mut self, destination: AccountId) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Add the destination
+            if !self.allowed_destinations.contains(&destination) {
+                if self.allowed_destinations.len() == MAX_ALLOWED_DESTINATIONS as usize {
+                    return Err(MultisigError::MaxAllowedDestinationsReached);
+                }
+                self.allowed_destinations.push(destination);
+            }
+
+            // emit event
+            Self::env().emit_event(DestinationAllowed { destination });
+
+            Ok(())
         }
 
-        /// Is Transaction Valid
-        /// The parameter of the transaction is the transaction id
-        /// Returns a result with () if the transaction id is valid or an Error if it is not valid
+        /// Destination disallowance
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the destination's account id
+        /// The destination is removed from the allowlist
+        /// Emit DestinationDisallowed event
         #[ink(message)]
-        pub fn is_tx_valid(&self, tx_id: TxId) -> Result<(), MultisigError> {
-            self.txs
-                .contains(tx_id)
-                .then_some(())
-                .ok_or(MultisigError::InvalidTxId)
+        pub fn disallow_destination(
+            &mut self,
+            destination: AccountId,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Remove the destination
+            self.allowed_destinations.retain(|&x| x != destination);
+
+            // emit event
+            Self::env().emit_event(DestinationDisallowed { destination });
+
+            Ok(())
         }
 
-        /// Get Transaction Approvals
-        /// The parameter of the transaction is the transaction id
-        /// Returns the number of approvals for the transaction if the transaction id is valid or None if it is not valid
+        /// Selector denial
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the selector and the minimum threshold
+        /// The minimum threshold cannot be zero or greater than the number of owners
+        /// Proposals calling the selector are refused unless they require at least the
+        /// minimum threshold, which can be raised with a threshold policy
+        /// If owners are removed afterwards it is capped to the number of owners
+        /// Emit SelectorDenied event
         #[ink(message)]
-        pub fn get_tx_approvals(&self, tx_id: TxId) -> Option<u8> {
-            self.approvals_count.get(tx_id)
+        pub fn deny_selector(
+            &mut self,
+            selector: [u8; 4],
+            min_threshold: u8,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Check that the minimum threshold is valid
+            ensure_threshold(min_threshold, self.owners_list.len())?;
+
+            // Deny the selector, replacing its previous minimum threshold
+            match self
+                .denied_selectors
+                .iter()
+                .position(|(x, _)| *x == selector)
+            {
+                Some(position) => self.denied_selectors[position].1 = min_threshold,
+                None if self.denied_selectors.len() == MAX_DENIED_SELECTORS as usize => {
+                    return Err(MultisigError::MaxDeniedSelectorsReached)
+                }
+                None => self.denied_selectors.push((selector, min_threshold)),
+            }
+
+            // emit event
+            Self::env().emit_event(SelectorDenied {
+                selector,
+                min_threshold,
+            });
+
+            Ok(())
         }
 
-        /// Get Transaction Rejections
-        /// The parameter of the transaction is the transaction id
-        /// Returns the number of rejections for the transaction if the transaction id is valid or None if it is not valid
+        /// Selector allowance
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the selector
+        /// The selector is removed from the denylist
+        /// Emit SelectorAllowed event
         #[ink(message)]
-        pub fn get_tx_rejections(&self, tx_id: TxId) -> Option<u8> {
-            self.rejections_count.get(tx_id)
+        pub fn allow_selector(&mut self, selector: [u8; 4]) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Allow the selector
+            self.denied_selectors
+                .retain(|(denied, _)| *denied != selector);
+
+            // emit event
+            Self::env().emit_event(SelectorAllowed { selector });
+
+            Ok(())
         }
 
-        /// Get Transaction Approval For Account
-        /// The parameters of the transaction are the transaction id and the account id
-        /// Returns true if the account has approved the transaction, false if the account has rejected the transaction or None if the transaction id is not valid
+        // Delegate calls
+        /// Delegate code hash allowance
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the code hash
+        /// The code must implement storage_layout_version, which is delegate called to check that
+        /// it was written against the current storage layout version
+        /// Transactions can delegate call the code hash once allowed
+        /// Emit DelegateCodeHashAllowed event
         #[ink(message)]
-        pub fn get_tx_approval_for_account(&self, tx_id: TxId, owner: AccountId) -> Option<bool> {
-            self.approvals.get((tx_id, owner))
-        }
-    }
+        pub fn allow_delegate_code_hash(&mut self, code_hash: Hash) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Check that the code was written against the current storage layout
+            let layout_version = match self.checked_delegate_call::<u32>(
+                code_hash,
+                SCRIPT_STORAGE_LAYOUT_VERSION_SELECTOR,
+                &[],
+                false,
+            )? {
+                Ok(Ok(layout_version)) => layout_version,
+                _ => return Err(MultisigError::IncompatibleStorageLayout),
+            };
+            if layout_version != STORAGE_LAYOUT_VERSION {
+                return Err(MultisigError::IncompatibleStorageLayout);
+            }
+
+            // Allow the code hash
+            if self.get_delegate_code_hash(code_hash).is_none() {
+                if self.delegate_code_hashes.len() == MAX_DELEGATE_CODE_HASHES as usize {
+                    return Err(MultisigError::MaxDelegateCodeHashesReached);
+                }
+                self.delegate_code_hashes.push((code_hash, layout_version));
+            }
+
+            // emit event
+            Self::env().emit_event(DelegateCodeHashAllowed {
+                code_hash,
+                layout_version,
+            });
+
+            Ok(())
+        }
+
+        /// Delegate code hash disallowance
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the code hash
+        /// The code hash cannot be delegate called anymore
+        /// Emit DelegateCodeHashDisallowed event
+        #[ink(message)]
+        pub fn disallow_delegate_code_hash(
+            &mut self,
+            code_hash: Hash,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Disallow the code hash
+            self.delegate_code_hashes
+                .retain(|(hash, _)| *hash != code_hash);
+
+            // emit event
+            Self::env().emit_event(DelegateCodeHashDisallowed { code_hash });
+
+            Ok(())
+        }
+
+        // Vote delegation
+        /// Vote delegation
+        /// The caller of this function must be an owner
+        /// The parameters of the transaction are the delegate's account id and the block number at which
+        /// the delegation expires
+        /// The delegate cannot be an owner and the expiry must be in the future
+        /// The delegate cannot vote on behalf of another owner at the same time
+        /// A previous delegation of the owner is replaced
+        /// Emit VotesDelegated event
+        #[ink(message)]
+        pub fn delegate_votes(
+            &mut self,
+            delegate: AccountId,
+            expires_at: BlockNumber,
+        ) -> Result<(), MultisigError> {
+            let owner = self.env().caller();
+
+            // Check that the caller is an owner
+            self.ensure_is_owner(owner)?;
+
+            // Check that the delegate is not an owner and the expiry is in the future
+            if self.owners_list.contains(&delegate) || expires_at <= self.env().block_number() {
+                return Err(MultisigError::InvalidDelegation);
+            }
+
+            // Check that the delegate does not vote on behalf of another owner
+            match self.active_delegator(delegate) {
+                Some(delegator) if delegator != owner => {
+                    return Err(MultisigError::DelegateAlreadyAssigned)
+                }
+                _ => {}
+            }
+
+            // Replace the previous delegation of the owner
+            self.remove_delegation(owner);
+            self.delegations.insert(
+                owner,
+                &Delegation {
+                    delegate,
+                    expires_at,
+                },
+            );
+            self.delegators.insert(delegate, &owner);
+
+            // emit event
+            Self::env().emit_event(VotesDelegated {
+                owner,
+                delegate,
+                expires_at,
+            });
+
+            Ok(())
+        }
+
+        /// Delegation revocation
+        /// The caller of this function must be an owner
+        /// The owner must have delegated its votes, expired delegations can be revoked too
+        /// The votes already cast by the delegate are kept
+        /// Emit DelegationRevoked event
+        #[ink(message)]
+        pub fn revoke_delegation(&mut self) -> Result<(), MultisigError> {
+            let owner = self.env().caller();
+
+            // Check that the caller is an owner
+            self.ensure_is_owner(owner)?;
+
+            let delegation = self
+                .remove_delegation(owner)
+                .ok_or(MultisigError::NoDelegation)?;
+
+            // emit event
+            Self::env().emit_event(DelegationRevoked {
+                owner,
+                delegate: delegation.delegate,
+            });
+
+            Ok(())
+        }
+
+        // Nested multisigs
+        /// Transaction proposal in an outer multisig
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the outer multisig and the transaction to propose in it
+        /// This multisig must be an owner of the outer multisig
+        /// The outer multisig can reenter this one while it executes the transaction
+        #[ink(message)]
+        pub fn propose_in(
+            &mut self,
+            outer: AccountId,
+            tx: Transaction,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.call_outer(outer, PROPOSE_TX_SELECTOR, tx)
+        }
+
+        /// Transaction approval in an outer multisig
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the outer multisig and the transaction Id in it
+        /// This multisig must be an owner of the outer multisig
+        /// The outer multisig can reenter this one while it executes the transaction
+        #[ink(message)]
+        pub fn approve_in(&mut self, outer: AccountId, tx_id: TxId) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.call_outer(outer, APPROVE_TX_SELECTOR, tx_id)
+        }
+
+        /// Transaction rejection in an outer multisig
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the outer multisig and the transaction Id in it
+        /// This multisig must be an owner of the outer multisig
+        #[ink(message)]
+        pub fn reject_in(&mut self, outer: AccountId, tx_id: TxId) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.call_outer(outer, REJECT_TX_SELECTOR, tx_id)
+        }
+
+        // Emergency pause
+        /// Pause
+        /// The caller of this function must be an owner or the pause guardian
+        /// Transactions cannot be proposed or executed until the pause expires or the wallet is unpaused,
+        /// except the ones that unpause it or manage the owners and the pause guardian
+        /// A paused wallet cannot be paused again, so the pause always expires
+        /// The pause guardian must also wait one pause duration after the last pause expired or was lifted,
+        /// so the owners get a window to evict it before it pauses the wallet again
+        /// The inheritance cannot be claimed while paused, but a recovery can be executed
+        /// Emit Paused event
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), MultisigError> {
+            let caller = self.env().caller();
+
+            // Check that the caller is an owner or the pause guardian
+            if !self.owners_list.contains(&caller) && self.pause_guardian != Some(caller) {
+                return Err(MultisigError::Unauthorized);
+            }
+
+            // Check that the pause is not extended
+            if self.is_paused() {
+                return Err(MultisigError::Paused);
+            }
+
+            // Check that the pause guardian is not pausing again within the cooldown
+            let now = self.env().block_number();
+            if !self.owners_list.contains(&caller)
+                && matches!(self.paused_until, Some(last) if now < last.saturating_add(self.pause_duration))
+            {
+                return Err(MultisigError::PauseCooldown);
+            }
+
+            let until = self
+                .env()
+                .block_number()
+                .saturating_add(self.pause_duration);
+            self.paused_until = Some(until);
+
+            // emit event
+            Self::env().emit_event(Paused { by: caller, until });
+
+            Ok(())
+        }
+
+        /// Unpause
+        /// The caller of this function must be the multisig contract itself
+        /// The transactions calling it can be proposed and executed while the wallet is paused
+        /// The pause is lifted at the current block, so the cooldown of the pause guardian starts from it
+        /// Emit Unpaused event
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.paused_until = Some(self.env().block_number());
+
+            // emit event
+            Self::env().emit_event(Unpaused {});
+
+            Ok(())
+        }
+
+        /// Pause guardian change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the new pause guardian, None to remove it
+        /// Emit PauseGuardianChanged event
+        #[ink(message)]
+        pub fn set_pause_guardian(
+            &mut self,
+            pause_guardian: Option<AccountId>,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.pause_guardian = pause_guardian;
+
+            // emit event
+            Self::env().emit_event(PauseGuardianChanged { pause_guardian });
+
+            Ok(())
+        }
+
+        /// Pause duration change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the number of blocks a pause lasts
+        /// The duration cannot be zero, and it applies to the following pauses
+        /// Emit PauseDurationChanged event
+        #[ink(message)]
+        pub fn set_pause_duration(&mut self, duration: BlockNumber) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Check that the duration is not zero
+            if duration == 0 {
+                return Err(MultisigError::InvalidPauseDuration);
+            }
+
+            self.pause_duration = duration;
+
+            // emit event
+            Self::env().emit_event(PauseDurationChanged { duration });
+
+            Ok(())
+        }
+
+        // Inheritance
+        /// Inheritance configuration change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the new configuration, None to disable the inheritance
+        /// The beneficiaries cannot be empty, and the inactivity period cannot be zero
+        /// The beneficiaries taking control must be valid owners with the given threshold
+        /// Emit InheritanceConfigChanged event
+        #[ink(message)]
+        pub fn set_inheritance_config(
+            &mut self,
+            config: Option<InheritanceConfig>,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Check that the configuration is valid
+            let config = match config {
+                Some(config) => Some(ensure_inheritance_config(config)?),
+                None => None,
+            };
+
+            // The configuration is changed with the owners approvals, so they are active
+            self.last_activity = self.env().block_number();
+            self.inheritance_config = config.clone();
+
+            // emit event
+            Self::env().emit_event(InheritanceConfigChanged { config });
+
+            Ok(())
+        }
+
+        /// Heartbeat
+        /// The caller of this function must be an owner
+        /// Proves the liveness of the owner, postponing the inheritance claim
+        /// Proposing, approving and rejecting transactions also count as activity, unless the votes are cast by a delegate
+        /// No event is emitted to keep it cheap
+        #[ink(message)]
+        pub fn heartbeat(&mut self) -> Result<(), MultisigError> {
+            // Check that the caller is an owner
+            self.ensure_is_owner(self.env().caller())?;
+
+            self.record_activity(self.env().caller());
+
+            Ok(())
+        }
+
+        /// Inheritance claim
+        /// The caller of this function must be a beneficiary
+        /// No owner can have been active during the inactivity period
+        /// The configured action is performed and the inheritance is disabled
+        /// Taking control removes the active transactions and replaces the owners by the beneficiaries
+        /// It also cancels the pending recovery, disables the modules and removes the guard, since they
+        /// were set up by the former owners. The recovery configuration and the pause guardian are kept:
+        /// any new owner can cancel a recovery during its delay, and a pause cannot move funds
+        /// The wallet cannot be paused
+        /// Emit InheritanceClaimed event, and ConfigChanged event or Transfer event depending on the action
+        #[ink(message)]
+        pub fn claim_inheritance(&mut self) -> Result<(), MultisigError> {
+            let beneficiary = self.env().caller();
+            let config = match &self.inheritance_config {
+                Some(config) if config.beneficiaries.contains(&beneficiary) => config.clone(),
+                _ => return Err(MultisigError::NotBeneficiary),
+            };
+
+            // Check that the owners have been inactive during the whole period
+            if self.env().block_number() < self.inheritance_claimable_at(&config) {
+                return Err(MultisigError::OwnersStillActive);
+            }
+
+            // Check that the wallet is not paused, the claim moves its funds or control
+            if self.is_paused() {
+                return Err(MultisigError::Paused);
+            }
+            self.inheritance_config = None;
+
+            match config.action {
+                InheritanceAction::TakeControl(threshold) => {
+                    self.replace_owners(&config.beneficiaries, threshold)?;
+                    self.revoke_control_paths();
+                }
+                InheritanceAction::Sweep(to) => {
+                    // Transferring the minimum balance would reap the account of the multisig
+                    let value = self
+                        .env()
+                        .balance()
+                        .saturating_sub(self.env().minimum_balance());
+                    self.env()
+                        .transfer(to, value)
+                        .map_err(|_| MultisigError::TransferFailed)?;
+
+                    // emit event
+                    Self::env().emit_event(Transfer { to, value });
+                }
+            }
+
+            // emit event
+            Self::env().emit_event(InheritanceClaimed {
+                beneficiary,
+                action: config.action,
+            });
+
+            Ok(())
+        }
+
+        // Social recovery
+        /// Recovery configuration change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the new configuration, None to disable the social recovery
+        /// The guardians cannot be empty or duplicated, and the maximum number of guardians is defined by MAX_GUARDIANS
+        /// The quorum cannot be zero or greater than the number of guardians
+        /// The delay cannot be lower than MIN_RECOVERY_DELAY
+        /// The pending recovery, if any, is cancelled
+        /// Emit RecoveryConfigChanged event
+        #[ink(message)]
+        pub fn set_recovery_config(
+            &mut self,
+            config: Option<RecoveryConfig>,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Check that the configuration is valid
+            if let Some(config) = &config {
+                ensure_recovery_config(config)?;
+            }
+
+            // The pending recovery was approved by the former guardians
+            self.cancel_pending_recovery();
+
+            // Change the configuration
+            self.recovery_config = config.clone();
+
+            // emit event
+            Self::env().emit_event(RecoveryConfigChanged { config });
+
+            Ok(())
+        }
+
+        /// Recovery proposal
+        /// The caller of this function must be a guardian
+        /// The parameters of the transaction are the owners and the threshold that replace the current ones
+        /// The owners and threshold must be valid with the same rules as the constructor params
+        /// There cannot be another pending recovery, unless it has expired, in which case it is replaced
+        /// The recovery is initialized with the approval of the proposer
+        /// It expires if it does not reach the quorum within RECOVERY_PROPOSAL_PERIOD
+        /// Emit RecoveryProposed event, RecoveryCancelled event if an expired recovery is replaced,
+        /// and RecoveryQuorumReached event if the quorum is 1
+        #[ink(message)]
+        pub fn propose_recovery(
+            &mut self,
+            owners: Vec<AccountId>,
+            threshold: u8,
+        ) -> Result<(), MultisigError> {
+            let guardian = self.env().caller();
+
+            // Check that caller is a guardian
+            self.ensure_is_guardian(guardian)?;
+
+            // Check that there is no pending recovery, an expired one is replaced
+            match &self.recovery {
+                Some(recovery) if !self.is_recovery_expired(recovery) => {
+                    return Err(MultisigError::RecoveryAlreadyPending);
+                }
+                Some(_) => {
+                    self.cancel_pending_recovery();
+                }
+                None => {}
+            }
+
+            // Remove duplicated owners and check that the owners and threshold are valid
+            let mut owners = owners;
+            owners.sort_unstable();
+            owners.dedup();
+            if owners.len() > MAX_OWNERS as usize {
+                return Err(MultisigError::MaxOwnersReached);
+            }
+            ensure_creation_params(threshold, &owners)?;
+
+            self.recovery = Some(Recovery {
+                owners: owners.clone(),
+                threshold,
+                approvals: Vec::new(),
+                rejections: Vec::new(),
+                executable_at: None,
+                expires_at: self
+                    .env()
+                    .block_timestamp()
+                    .saturating_add(RECOVERY_PROPOSAL_PERIOD),
+            });
+
+            // emit event
+            Self::env().emit_event(RecoveryProposed {
+                guardian,
+                owners,
+                threshold,
+            });
+
+            self.approve_pending_recovery(guardian)
+        }
+
+        /// Recovery approval
+        /// The caller of this function must be a guardian that has not voted the pending recovery yet
+        /// The recovery cannot have expired
+        /// The recovery delay starts when the quorum is reached
+        /// Emit RecoveryApproved event, and RecoveryQuorumReached event when the quorum is reached
+        #[ink(message)]
+        pub fn approve_recovery(&mut self) -> Result<(), MultisigError> {
+            let guardian = self.env().caller();
+
+            // Check that caller is a guardian
+            self.ensure_is_guardian(guardian)?;
+
+            self.approve_pending_recovery(guardian)?;
+
+            // emit event
+            Self::env().emit_event(RecoveryApproved { guardian });
+
+            Ok(())
+        }
+
+        /// Recovery rejection
+        /// The caller of this function must be a guardian that has not voted the pending recovery yet
+        /// The recovery cannot have expired or reached the quorum
+        /// When the remaining guardians cannot reach the quorum anymore the recovery is discarded,
+        /// so a single guardian cannot keep a bogus proposal pending
+        /// Emit RecoveryRejected event, and RecoveryCancelled event if the recovery is discarded
+        #[ink(message)]
+        pub fn reject_recovery(&mut self) -> Result<(), MultisigError> {
+            let guardian = self.env().caller();
+
+            // Check that caller is a guardian
+            self.ensure_is_guardian(guardian)?;
+
+            let guardians_count = self
+                .recovery_config
+                .as_ref()
+                .map(|config| (config.guardians.len(), config.quorum as usize));
+            let (guardians_count, quorum) = guardians_count.ok_or(MultisigError::NotGuardian)?;
+            let mut recovery = self.pending_recovery_to_vote(guardian)?;
+
+            // Once the quorum is reached only the owners can cancel the recovery
+            if recovery.executable_at.is_some() {
+                return Err(MultisigError::RecoveryQuorumAlreadyReached);
+            }
+            recovery.rejections.push(guardian);
+            let rejections_count = recovery.rejections.len();
+            self.recovery = Some(recovery);
+
+            // emit event
+            Self::env().emit_event(RecoveryRejected { guardian });
+
+            // Discard the recovery if the quorum cannot be reached anymore
+            if guardians_count.saturating_sub(rejections_count) < quorum {
+                self.cancel_pending_recovery();
+            }
+
+            Ok(())
+        }
+
+        /// Recovery cancellation
+        /// The caller of this function must be an owner
+        /// A single owner can cancel it, since the remaining owners may not reach the threshold when keys are lost
+        /// The pending recovery is discarded
+        /// Emit RecoveryCancelled event
+        #[ink(message)]
+        pub fn cancel_recovery(&mut self) -> Result<(), MultisigError> {
+            // Check that the caller is an owner
+            self.ensure_is_owner(self.env().caller())?;
+
+            // Check that there is a pending recovery
+            if !self.cancel_pending_recovery() {
+                return Err(MultisigError::NoPendingRecovery);
+            }
+
+            Ok(())
+        }
+
+        /// Recovery execution
+        /// Anyone can execute the pending recovery once it has reached the quorum and its delay has elapsed
+        /// It can be executed while the wallet is paused, since it evicts the owners that could keep it paused
+        /// The active transactions are removed, since they were voted by the replaced owners
+        /// The owners and the threshold are replaced
+        /// The modules are disabled and the guard is removed, since they were set up by the replaced owners
+        /// and could be controlled by the same keys. The recovery configuration and the pause guardian are kept
+        /// Emit TransactionRemoved events, ConfigChanged event, ModuleDisabled events, GuardChanged event
+        /// and RecoveryExecuted event
+        #[ink(message)]
+        pub fn execute_recovery(&mut self) -> Result<(), MultisigError> {
+            let recovery = self
+                .recovery
+                .clone()
+                .ok_or(MultisigError::NoPendingRecovery)?;
+
+            // Check that the quorum has been reached and the delay has elapsed
+            let now = self.env().block_timestamp();
+            if !matches!(recovery.executable_at, Some(executable_at) if now >= executable_at) {
+                return Err(MultisigError::RecoveryNotExecutable);
+            }
+            self.recovery = None;
+
+            self.replace_owners(&recovery.owners, recovery.threshold)?;
+            self.revoke_control_paths();
+
+            // emit event
+            Self::env().emit_event(RecoveryExecuted {
+                owners: recovery.owners,
+                threshold: recovery.threshold,
+            });
+
+            Ok(())
+        }
+
+        // Staking
+        /// Staking pallets change
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the indices of the staking pallets in the runtime,
+        /// None to disable the staking helpers
+        /// The staking helpers cannot be used until the indices are configured
+        /// Emit StakingPalletsChanged event
+        #[ink(message)]
+        pub fn set_staking_pallets(
+            &mut self,
+            pallets: Option<StakingPallets>,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.staking_pallets = pallets;
+
+            // emit event
+            Self::env().emit_event(StakingPalletsChanged { pallets });
+
+            Ok(())
+        }
+
+        /// Bond
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the value to bond and the rewards destination
+        /// The multisig account is the stash of the bonded funds
+        /// Emit StakingOperationPerformed event
+        #[ink(message)]
+        pub fn bond(
+            &mut self,
+            value: Balance,
+            payee: RewardDestination,
+        ) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.perform_staking_operation(StakingOperation::Bond { value, payee })
+        }
+
+        /// Nominate
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the list of validators to nominate
+        /// The funds must have been bonded before
+        /// Emit StakingOperationPerformed event
+        #[ink(message)]
+        pub fn nominate(&mut self, targets: Vec<AccountId>) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.perform_staking_operation(StakingOperation::Nominate { targets })
+        }
+
+        /// Unbond
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the value to unbond
+        /// The value can be withdrawn once the unbonding period is over
+        /// Emit StakingOperationPerformed event
+        #[ink(message)]
+        pub fn unbond(&mut self, value: Balance) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.perform_staking_operation(StakingOperation::Unbond { value })
+        }
+
+        /// Withdraw Unbonded
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the number of slashing spans of the stash
+        /// The unbonded funds whose unbonding period is over are withdrawn
+        /// Emit StakingOperationPerformed event
+        #[ink(message)]
+        pub fn withdraw_unbonded(&mut self, num_slashing_spans: u32) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.perform_staking_operation(StakingOperation::WithdrawUnbonded {
+                num_slashing_spans,
+            })
+        }
+
+        /// Join Pool
+        /// The caller of this function must be the multisig contract itself
+        /// The parameters of the transaction are the amount to bond and the nomination pool id
+        /// Emit StakingOperationPerformed event
+        #[ink(message)]
+        pub fn join_pool(&mut self, amount: Balance, pool_id: u32) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.perform_staking_operation(StakingOperation::JoinPool { amount, pool_id })
+        }
+
+        /// Claim Pool Payout
+        /// The caller of this function must be the multisig contract itself
+        /// The pending rewards of the nomination pool are paid to the multisig
+        /// Emit StakingOperationPerformed event
+        #[ink(message)]
+        pub fn claim_pool_payout(&mut self) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            self.perform_staking_operation(StakingOperation::ClaimPoolPayout)
+        }
+
+        /// Transfer funds from the contract to another account
+        /// The caller of this function must be the multisig contract itself
+        /// The parameter of the transaction is the receiver's account id and the amount to be transferred
+        /// The transfer is performed
+        /// Emit Transfer event
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<(), MultisigError> {
+            // Check that caller is multisig
+            self.ensure_self_call()?;
+
+            // Transfer the funds
+            // Balance checks are being done inside the transfer function
+            self.env()
+                .transfer(to, value)
+                .map_err(|_| MultisigError::TransferFailed)?;
+
+            // emit event
+            Self::env().emit_event(Transfer { to, value });
+
+            Ok(())
+        }
+
+        //-------------------------------------------------------
+        // Internal functions
+        //-------------------------------------------------------
+
+        fn ensure_self_call(&self) -> Result<(), MultisigError> {
+            if self.env().caller() != self.env().account_id() {
+                return Err(MultisigError::Unauthorized);
+            }
+            Ok(())
+        }
+
+        fn ensure_is_owner(&self, owner: AccountId) -> Result<(), MultisigError> {
+            self.owners_list
+                .contains(&owner)
+                .then_some(())
+                .ok_or(MultisigError::NotOwner)
+        }
+
+        fn ensure_is_module(&self, module: AccountId) -> Result<(), MultisigError> {
+            self.modules_list
+                .contains(&module)
+                .then_some(())
+                .ok_or(MultisigError::NotModule)
+        }
+
+        fn ensure_destination_allowed(&self, destination: AccountId) -> Result<(), MultisigError> {
+            self.is_destination_allowed(destination)
+                .then_some(())
+                .ok_or(MultisigError::DestinationNotAllowed)
+        }
+
+        fn ensure_tx_allowed(&self, tx: &Transaction) -> Result<(), MultisigError> {
+            match &tx.call_type {
+                // Transfers are self calls, so their receiver is checked instead
+                CallType::Call if self.is_transfer_tx(tx) => {
+                    let receiver = <AccountId as scale::Decode>::decode(&mut &tx.input[..])
+                        .map_err(|_| MultisigError::DestinationNotAllowed)?;
+                    self.ensure_destination_allowed(receiver)?
+                }
+                CallType::Call
+                    if tx.address == self.env().account_id()
+                        && STAKING_SELECTORS.contains(&tx.selector) =>
+                {
+                    self.ensure_allowlist_disabled()?
+                }
+                CallType::Call => self.ensure_destination_allowed(tx.address)?,
+                CallType::DelegateCall(code_hash) => {
+                    self.ensure_delegate_call_allowed(*code_hash)?
+                }
+                CallType::Instantiate { .. } | CallType::RuntimeCall => {
+                    self.ensure_allowlist_disabled()?
+                }
+            }
+            self.ensure_selector_allowed(tx)
+        }
+
+        fn ensure_allowlist_disabled(&self) -> Result<(), MultisigError> {
+            (!self.destination_allowlist_enabled)
+                .then_some(())
+                .ok_or(MultisigError::DestinationNotAllowed)
+        }
+
+        fn ensure_delegate_call_allowed(&self, code_hash: Hash) -> Result<(), MultisigError> {
+            match self.get_delegate_code_hash(code_hash) {
+                Some(STORAGE_LAYOUT_VERSION) => Ok(()),
+                Some(_) => Err(MultisigError::IncompatibleStorageLayout),
+                None => Err(MultisigError::DelegateCallNotAllowed),
+            }
+        }
+
+        fn ensure_selector_allowed(&self, tx: &Transaction) -> Result<(), MultisigError> {
+            match self.get_denied_selector(tx.selector) {
+                Some(min_threshold) if self.tx_threshold(tx) < min_threshold => {
+                    Err(MultisigError::SelectorDenied)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        fn ensure_not_already_voted(
+            &self,
+            tx_id: TxId,
+            owner: AccountId,
+        ) -> Result<(), MultisigError> {
+            if self.tx_vote(tx_id, owner).is_some() {
+                return Err(MultisigError::AlreadyVoted);
+            }
+            Ok(())
+        }
+
+        fn check_threshold_met(&self, tx_id: TxId) -> bool {
+            // Fetch the approvals for the transaction
+            let votes = self.votes.get(tx_id).expect("This should never fail. We are fetching the votes for a transaction that we know exists");
+            votes.approvals_count >= self.required_approvals(tx_id)
+        }
+
+        fn check_threshold_can_be_met(&self, tx_id: TxId) -> bool {
+            // Fetch the rejections for the transaction
+            let votes = self.votes.get(tx_id).expect("This should never fail. We are fetching the votes for a transaction that we know exists");
+
+            // if the rejection threshold is reached, then the transaction is vetoed
+            let owners_count = self.owners_list.len() as u8;
+            if let Some(rejection_threshold) = self.rejection_threshold {
+                if votes.rejections_count >= rejection_threshold.min(owners_count) {
+                    return false;
+                }
+            }
+
+            // if the rejections are greater than owners - threshold, then the threshold can't be met
+            votes.rejections_count <= owners_count.saturating_sub(self.required_approvals(tx_id))
+        }
+
+        fn required_approvals(&self, tx_id: TxId) -> u8 {
+            let tx = self.txs.get(tx_id).expect(
+                "This should never fail. We are fetching a transaction that we know exists",
+            );
+            self.tx_threshold(&tx)
+        }
+
+        fn policy(
+            &self,
+            address: AccountId,
+            selector: [u8; 4],
+        ) -> Option<&Vec<ThresholdPolicyRange>> {
+            self.policies
+                .iter()
+                .find(|(key, _)| *key == (address, selector))
+                .map(|(_, ranges)| ranges)
+        }
+
+        fn tx_threshold(&self, tx: &Transaction) -> u8 {
+            let owners_count = self.owners_list.len() as u8;
+
+            // The policy matching the transaction takes precedence
+            let policy_threshold = self.policy(tx.address, tx.selector).and_then(|ranges| {
+                ranges
+                    .iter()
+                    .find(|range| range.contains(tx.transferred_value))
+                    .map(|range| range.threshold)
+            });
+
+            let threshold = match policy_threshold {
+                Some(threshold) => threshold,
+                None if self.is_admin_tx(tx) => self.get_admin_threshold(),
+                None => self.threshold,
+            };
+
+            // Owners may have been removed after the policy was set, so it is capped
+            // to the number of owners in order to keep the transactions executable
+            threshold.min(owners_count)
+        }
+
+        fn is_admin_tx(&self, tx: &Transaction) -> bool {
+            // Delegate calls run in the context of the multisig, and runtime calls are dispatched on
+            // its behalf, so they are as powerful as admin self calls
+            match tx.call_type {
+                CallType::Call => {
+                    tx.address == self.env().account_id() && tx.selector != TRANSFER_SELECTOR
+                }
+                CallType::DelegateCall(_) | CallType::RuntimeCall => true,
+                CallType::Instantiate { .. } => false,
+            }
+        }
+
+        fn perform_approval_rejection_checking(
+            &mut self,
+            tx_id: TxId,
+        ) -> Result<(AccountId, Option<AccountId>), MultisigError> {
+            // Check that the caller is an owner or votes on behalf of one
+            let (owner, delegate) = self.voter()?;
+
+            // Check that the transaction exists
+            self.is_tx_valid(tx_id)?;
+
+            // Check that the owner has not voted yet
+            self.ensure_not_already_voted(tx_id, owner)?;
+
+            Ok((owner, delegate))
+        }
+
+        /// Returns the owner the caller votes as, with the caller if it is a delegate
+        fn voter(&self) -> Result<(AccountId, Option<AccountId>), MultisigError> {
+            let caller = self.env().caller();
+            if self.owners_list.contains(&caller) {
+                return Ok((caller, None));
+            }
+            self.active_delegator(caller)
+                .map(|owner| (owner, Some(caller)))
+                .ok_or(MultisigError::NotOwner)
+        }
+
+        fn active_delegator(&self, delegate: AccountId) -> Option<AccountId> {
+            let owner = self.delegators.get(delegate)?;
+            let delegation = self.delegations.get(owner)?;
+            (self.owners_list.contains(&owner)
+                && delegation.delegate == delegate
+                && self.env().block_number() < delegation.expires_at)
+                .then_some(owner)
+        }
+
+        fn remove_delegation(&mut self, owner: AccountId) -> Option<Delegation> {
+            let delegation = self.delegations.get(owner)?;
+            self.delegations.remove(owner);
+
+            // The delegate may vote on behalf of another owner once this delegation has expired
+            if self.delegators.get(delegation.delegate) == Some(owner) {
+                self.delegators.remove(delegation.delegate);
+            }
+            Some(delegation)
+        }
+
+        fn _try_execute_tx(&mut self, tx_id: TxId) -> Result<(), MultisigError> {
+            // While paused the approved transactions stay pending, they can be executed with
+            // try_execute_tx once the wallet is unpaused
+            let tx = self.txs.get(tx_id).expect(
+                "This should never fail. We are fetching a transaction that we know exists",
+            );
+            if self.ensure_not_paused(&tx).is_err() {
+                return Ok(());
+            }
+
+            // check threshold met
+            if self.check_threshold_met(tx_id) {
+                // execute transaction
+                self.execute_tx(tx_id)?;
+            }
+            Ok(())
+        }
+
+        fn execute_tx(&mut self, tx_id: TxId) -> Result<(), MultisigError> {
+            // Fetch the transaction
+            let tx = self.get_tx(tx_id).expect("This should never fail because we are checking the tx_id before calling this function");
+
+            let result = self.perform_tx(tx_id, &tx)?;
+
+            // Delete the transaction from the storage
+            self.remove_tx(tx_id);
+
+            // Emit event
+            Self::env().emit_event(TransactionExecuted { tx_id, result });
+
+            Ok(())
+        }
+
+        fn perform_tx(&mut self, tx_id: TxId, tx: &Transaction) -> Result<TxResult, MultisigError> {
+            // The lists may have changed since the transaction was proposed
+            if let Err(e) = self.ensure_tx_allowed(tx) {
+                return Ok(TxResult::Failed(e));
+            }
+
+            // Transactions that replace the guard are not checked, so a faulty guard can't lock the wallet
+            match self.guard.filter(|_| !self.is_set_guard_tx(tx)) {
+                None => self.perform_call(tx_id, tx),
+                // If the guard rejects the transaction it is not performed and it is marked as failed
+                Some(guard) if !guard_approves(guard_check_transaction(guard, tx_id, tx)) => {
+                    Ok(TxResult::Failed(MultisigError::GuardRejected))
+                }
+                Some(_) => Ok(self.perform_guarded_call(tx_id, tx)),
+            }
+        }
+
+        fn perform_guarded_call(&mut self, tx_id: TxId, tx: &Transaction) -> TxResult {
+            // The transaction is performed in a nested call of the multisig to itself, so if the
+            // guard rejects its outcome every change made by it is reverted with the nested call
+            self.guarded_tx = Some(tx_id);
+            self.write_root();
+
+            let result = call_perform_guarded_tx(self.env().account_id(), tx_id, tx);
+
+            // The nested call has changed the storage, or it has been reverted with it
+            self.read_root();
+            self.guarded_tx = None;
+
+            result
+        }
+
+        fn perform_call(
+            &mut self,
+            tx_id: TxId,
+            tx: &Transaction,
+        ) -> Result<TxResult, MultisigError> {
+            // Save current changes to storage, so the calls reentering the contract
+            // read the same state this execution has
+            self.write_root();
+
+            let result = match &tx.call_type {
+                CallType::Call => self.call(tx),
+                CallType::DelegateCall(code_hash) => self.delegate_call(*code_hash, tx)?,
+                CallType::Instantiate { code_hash, salt } => {
+                    self.instantiate(tx_id, *code_hash, salt, tx)
+                }
+                CallType::RuntimeCall => match dispatch_runtime_call(&tx.input) {
+                    Ok(()) => TxResult::Success(Vec::new()),
+                    Err(e) => TxResult::Failed(MultisigError::from(e)),
+                },
+            };
+
+            // We need to read the storage again because the call might have reentered the
+            // contract and changed it, either with a self call or through another contract.
+            // Otherwise the stale values would overwrite those changes when the message ends
+            if tx.allow_reentry {
+                self.read_root();
+            }
+
+            Ok(result)
+        }
+
+        fn call(&mut self, tx: &Transaction) -> TxResult {
+            let tx_result = call_contract(tx);
+
+            // Instead of just returning a custom Error we could return the error from the call
+            match tx_result {
+                Ok(Ok(OutputBytes(bytes))) => TxResult::Success(bytes),
+                Ok(Err(e)) => TxResult::Failed(MultisigError::LangExecutionFailed(e)),
+                Err(e) => TxResult::Failed(MultisigError::from(e)),
+            }
+        }
+
+        fn delegate_call(
+            &mut self,
+            code_hash: Hash,
+            tx: &Transaction,
+        ) -> Result<TxResult, MultisigError> {
+            // The code hash may have been disallowed after the transaction was proposed
+            if let Err(e) = self.ensure_delegate_call_allowed(code_hash) {
+                return Ok(TxResult::Failed(e));
+            }
+
+            let tx_result = self.checked_delegate_call::<OutputBytes>(
+                code_hash,
+                tx.selector,
+                &tx.input,
+                tx.allow_reentry,
+            )?;
+
+            Ok(match tx_result {
+                Ok(Ok(OutputBytes(bytes))) => TxResult::Success(bytes),
+                Ok(Err(e)) => TxResult::Failed(MultisigError::LangExecutionFailed(e)),
+                Err(e) => TxResult::Failed(MultisigError::from(e)),
+            })
+        }
+
+        fn instantiate(
+            &mut self,
+            tx_id: TxId,
+            code_hash: Hash,
+            salt: &[u8],
+            tx: &Transaction,
+        ) -> TxResult {
+            match instantiate_contract(code_hash, salt, tx) {
+                Ok(Ok(Ok(InstantiatedContract(contract)))) => {
+                    Self::env().emit_event(ContractInstantiated {
+                        tx_id,
+                        contract,
+                        code_hash,
+                    });
+                    TxResult::Instantiated(contract)
+                }
+                Ok(Ok(Err(OutputBytes(error)))) => {
+                    TxResult::Failed(MultisigError::ConstructorFailed(error))
+                }
+                Ok(Err(e)) => TxResult::Failed(MultisigError::LangExecutionFailed(e)),
+                Err(e) => TxResult::Failed(MultisigError::from(e)),
+            }
+        }
+
+        /// Delegate calls the code hash, aborting the whole execution if the delegated code
+        /// modifies the multisig storage or replaces its code
+        /// The stored root (owners, threshold, guard, modules, policies...), every mapping entry
+        /// the contract reads and the code hash are compared before and after the call.
+        /// A delegated code that terminates the contract cannot be detected, since the execution
+        /// never returns, which is why code hashes are allowed with an admin self call
+        fn checked_delegate_call<R: scale::Decode>(
+            &self,
+            code_hash: Hash,
+            selector: [u8; 4],
+            input: &[u8],
+            allow_reentry: bool,
+        ) -> Result<Result<ink::MessageResult<R>, EnvError>, MultisigError> {
+            let root_before = self.stored_root();
+            let mappings_before = self.mappings_snapshot();
+            let code_hash_before = own_code_hash();
+
+            let call_result = delegate_call_code::<R>(code_hash, selector, input, allow_reentry);
+
+            // Returning an error reverts every change made by the delegated code
+            if own_code_hash() != code_hash_before
+                || self.stored_root() != root_before
+                || self.mappings_snapshot() != mappings_before
+            {
+                return Err(MultisigError::StorageCorrupted);
+            }
+            Ok(call_result)
+        }
+
+        fn write_root(&self) {
+            ink::env::set_contract_storage(&<MultiSig as StorageKey>::KEY, self);
+        }
+
+        fn read_root(&mut self) {
+            *self = ink::env::get_contract_storage(&<MultiSig as StorageKey>::KEY)
+                .expect("This should never fail. The multisig root is always decodable")
+                .expect(
+                    "This should never fail. The multisig root has been written before the call",
+                );
+        }
+
+        fn stored_root(&self) -> Option<OutputBytes> {
+            ink::env::get_contract_storage(&<MultiSig as StorageKey>::KEY)
+                .expect("The multisig root is always decodable as raw bytes")
+        }
+
+        /// Encodes every mapping entry the contract reads: the active transactions with their
+        /// votes and vote delegates, the owner slots, the delegations with their delegates and
+        /// the staking records
+        /// The entries under other keys are overwritten before being read or only trusted when
+        /// they match these ones, so a delegated code cannot use them to change the wallet
+        fn mappings_snapshot(&self) -> Vec<u8> {
+            let mut snapshot = Vec::new();
+            for position in 0..self.active_txs_count {
+                let tx_id = self.active_txs.get(position);
+                scale::Encode::encode_to(&tx_id, &mut snapshot);
+                if let Some(tx_id) = tx_id {
+                    let votes = self.votes.get(tx_id);
+                    scale::Encode::encode_to(&self.active_txs_positions.get(tx_id), &mut snapshot);
+                    scale::Encode::encode_to(&self.txs.get(tx_id), &mut snapshot);
+                    scale::Encode::encode_to(&votes, &mut snapshot);
+                    for slot in (0..MAX_OWNER_SLOTS)
+                        .filter(|slot| matches!(&votes, Some(votes) if votes.is_delegated(*slot)))
+                    {
+                        scale::Encode::encode_to(
+                            &self.vote_delegates.get((tx_id, slot)),
+                            &mut snapshot,
+                        );
+                    }
+                }
+            }
+            for slot in 0..MAX_OWNER_SLOTS {
+                let slot_owner = self.slot_owners.get(slot);
+                scale::Encode::encode_to(&slot_owner, &mut snapshot);
+                if let Some(slot_owner) = slot_owner {
+                    scale::Encode::encode_to(&self.owner_slots.get(slot_owner), &mut snapshot);
+                }
+            }
+            for owner in self.owners_list.iter() {
+                let delegation = self.delegations.get(owner);
+                scale::Encode::encode_to(&self.owner_slots.get(owner), &mut snapshot);
+                scale::Encode::encode_to(&delegation, &mut snapshot);
+                if let Some(delegation) = delegation {
+                    scale::Encode::encode_to(
+                        &self.delegators.get(delegation.delegate),
+                        &mut snapshot,
+                    );
+                }
+            }
+            for index in 0..self.staking_records_count {
+                scale::Encode::encode_to(&self.staking_records.get(index), &mut snapshot);
+            }
+            snapshot
+        }
+
+        fn simulate(&mut self, tx_id: TxId, tx: &Transaction) -> ! {
+            let gas_before = gas_left();
+            let result = self.perform_tx(tx_id, tx).unwrap_or_else(TxResult::Failed);
+            let gas_consumed = gas_before.saturating_sub(gas_left());
+
+            // The simulation must never be committed, otherwise it would allow to execute
+            // transactions without approvals. Returning with the reverted flag discards
+            // every change while still handing the result to the caller
+            let simulation: Result<TxSimulation, MultisigError> = Ok(TxSimulation {
+                result,
+                gas_consumed,
+            });
+            return_value::<Result<Result<TxSimulation, MultisigError>, LangError>>(
+                ReturnFlags::REVERT,
+                &Ok(simulation),
+            )
+        }
+
+        fn perform_staking_operation(
+            &mut self,
+            operation: StakingOperation,
+        ) -> Result<(), MultisigError> {
+            // Check that the staking pallets are configured
+            let pallets = self
+                .staking_pallets
+                .ok_or(MultisigError::StakingNotConfigured)?;
+
+            // Dispatch the runtime call, a failure reverts the whole self call
+            dispatch_runtime_call(&scale::Encode::encode(&operation.runtime_call(pallets)))?;
+
+            // Record the operation
+            let index = self.staking_records_count;
+            self.staking_records.insert(
+                index,
+                &StakingRecord {
+                    operation: operation.clone(),
+                    block_number: self.env().block_number(),
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            self.staking_records_count = index.checked_add(1).expect(
+                "This should never fail. The staking records count cannot realistically overflow",
+            );
+
+            // emit event
+            Self::env().emit_event(StakingOperationPerformed { index, operation });
+
+            Ok(())
+        }
+
+        fn call_outer<Args: scale::Encode>(
+            &mut self,
+            outer: AccountId,
+            selector: [u8; 4],
+            args: Args,
+        ) -> Result<(), MultisigError> {
+            // The self call performing this message already required reentry to be allowed, so the
+            // outer multisig can reenter this one when the vote executes one of its transactions.
+            // Save current changes to storage, so those calls read the same state this message has
+            self.write_root();
+
+            let call_result = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
+                .call(outer)
+                .call_flags(CallFlags::ALLOW_REENTRY)
+                .exec_input(ExecutionInput::new(selector.into()).push_arg(args))
+                .returns::<Result<(), MultisigError>>()
+                .try_invoke();
+
+            // Otherwise the stale values would overwrite the changes of the reentrant calls
+            self.read_root();
+
+            // The error returned by the outer multisig makes this message fail
+            match call_result {
+                Ok(Ok(result)) => result,
+                Ok(Err(e)) => Err(MultisigError::LangExecutionFailed(e)),
+                Err(e) => Err(MultisigError::from(e)),
+            }
+        }
+
+        fn ensure_not_paused(&self, tx: &Transaction) -> Result<(), MultisigError> {
+            let is_exempt_tx = tx.call_type == CallType::Call
+                && tx.address == self.env().account_id()
+                && PAUSE_EXEMPT_SELECTORS.contains(&tx.selector);
+            if self.is_paused() && !is_exempt_tx {
+                return Err(MultisigError::Paused);
+            }
+            Ok(())
+        }
+
+        fn is_transfer_tx(&self, tx: &Transaction) -> bool {
+            tx.address == self.env().account_id() && tx.selector == TRANSFER_SELECTOR
+        }
+
+        fn is_set_guard_tx(&self, tx: &Transaction) -> bool {
+            tx.call_type == CallType::Call
+                && tx.address == self.env().account_id()
+                && tx.selector == SET_GUARD_SELECTOR
+        }
+
+        fn _try_remove_tx(&mut self, tx_id: TxId) {
+            // check if threshold can be met with the remaining approvals
+            if !self.check_threshold_can_be_met(tx_id) {
+                Self::env().emit_event(TransactionCancelled { tx_id });
+
+                // delete transaction
+                self.remove_tx(tx_id);
+            }
+        }
+
+        fn remove_tx(&mut self, tx_id: TxId) {
+            // Remove the transaction from the active ones
+            self.swap_remove_active_tx(tx_id);
+
+            // Remove the transaction from the mappping
+            self.txs.remove(tx_id);
+
+            // Remove the votes, including the ones of the voters that are not owners anymore
+            if let Some(votes) = self.votes.get(tx_id) {
+                for slot in voter_slots(votes.delegated) {
+                    self.vote_delegates.remove((tx_id, slot));
+                }
+            }
+            self.votes.remove(tx_id);
+
+            // emit event
+            Self::env().emit_event(TransactionRemoved { tx_id });
+        }
+
+        fn record_own_activity(&mut self, owner: AccountId, delegate: Option<AccountId>) {
+            // A vote cast by a delegate does not prove the liveness of the owner
+            if delegate.is_none() {
+                self.record_activity(owner);
+            }
+        }
+
+        fn record_activity(&mut self, owner: AccountId) {
+            let block_number = self.env().block_number();
+            self.last_activity = block_number;
+            match self
+                .owners_last_activity
+                .iter_mut()
+                .find(|(x, _)| *x == owner)
+            {
+                Some((_, last_activity)) => *last_activity = block_number,
+                None => self.owners_last_activity.push((owner, block_number)),
+            }
+        }
+
+        fn inheritance_claimable_at(&self, config: &InheritanceConfig) -> BlockNumber {
+            self.last_activity.saturating_add(config.inactivity_period)
+        }
+
+        fn replace_owners(
+            &mut self,
+            owners: &[AccountId],
+            threshold: u8,
+        ) -> Result<(), MultisigError> {
+            // Remove the active transactions, since they were voted by the replaced owners
+            for tx_id in self.active_txs_page(0, self.active_txs_count) {
+                self.remove_tx(tx_id);
+            }
+
+            // Replace the owners and the threshold
+            let owners_to_remove = self
+                .owners_list
+                .iter()
+                .filter(|owner| !owners.contains(owner))
+                .copied()
+                .collect();
+            let owners_to_add = owners
+                .iter()
+                .filter(|owner| !self.owners_list.contains(owner))
+                .copied()
+                .collect();
+            self.set_owners_and_threshold(owners_to_add, owners_to_remove, threshold)?;
+
+            // The threshold given with the new owners is a fixed one
+            self.clear_threshold_ratio();
+
+            Ok(())
+        }
+
+        fn threshold_for(&self, owners_count: usize) -> u8 {
+            match &self.threshold_ratio {
+                Some(threshold_ratio) => threshold_ratio.threshold(owners_count),
+                None => self.threshold,
+            }
+        }
+
+        fn update_threshold(&mut self, threshold: u8) {
+            if threshold != self.threshold {
+                self.threshold = threshold;
+
+                // emit event
+                Self::env().emit_event(ThresholdChanged { threshold });
+            }
+        }
+
+        fn clear_threshold_ratio(&mut self) {
+            if self.threshold_ratio.take().is_some() {
+                // emit event
+                Self::env().emit_event(ThresholdRatioChanged {
+                    threshold_ratio: None,
+                });
+            }
+        }
+
+        fn ensure_is_guardian(&self, guardian: AccountId) -> Result<(), MultisigError> {
+            match &self.recovery_config {
+                Some(config) if config.guardians.contains(&guardian) => Ok(()),
+                _ => Err(MultisigError::NotGuardian),
+            }
+        }
+
+        fn approve_pending_recovery(&mut self, guardian: AccountId) -> Result<(), MultisigError> {
+            let quorum = self
+                .recovery_config
+                .as_ref()
+                .map(|config| (config.quorum, config.delay));
+            let (quorum, delay) = quorum.ok_or(MultisigError::NotGuardian)?;
+            let mut recovery = self.pending_recovery_to_vote(guardian)?;
+            recovery.approvals.push(guardian);
+
+            // The delay starts when the quorum is reached
+            if recovery.executable_at.is_none() && recovery.approvals.len() >= quorum as usize {
+                let executable_at = self.env().block_timestamp().saturating_add(delay);
+                recovery.executable_at = Some(executable_at);
+
+                // emit event
+                Self::env().emit_event(RecoveryQuorumReached { executable_at });
+            }
+
+            self.recovery = Some(recovery);
+            Ok(())
+        }
+
+        fn pending_recovery_to_vote(&self, guardian: AccountId) -> Result<Recovery, MultisigError> {
+            let recovery = self
+                .recovery
+                .clone()
+                .ok_or(MultisigError::NoPendingRecovery)?;
+
+            // Check that the recovery has not expired
+            if self.is_recovery_expired(&recovery) {
+                return Err(MultisigError::RecoveryExpired);
+            }
+
+            // Check that the guardian has not voted yet
+            if recovery.approvals.contains(&guardian) || recovery.rejections.contains(&guardian) {
+                return Err(MultisigError::AlreadyVoted);
+            }
+
+            Ok(recovery)
+        }
+
+        fn is_recovery_expired(&self, recovery: &Recovery) -> bool {
+            recovery.executable_at.is_none() && self.env().block_timestamp() >= recovery.expires_at
+        }
+
+        fn revoke_control_paths(&mut self) {
+            self.cancel_pending_recovery();
+
+            for module in core::mem::take(&mut self.modules_list) {
+                // emit event
+                Self::env().emit_event(ModuleDisabled { module });
+            }
+
+            if self.guard.take().is_some() {
+                // emit event
+                Self::env().emit_event(GuardChanged { guard: None });
+            }
+        }
+
+        fn cancel_pending_recovery(&mut self) -> bool {
+            if self.recovery.take().is_none() {
+                return false;
+            }
+
+            // emit event
+            Self::env().emit_event(RecoveryCancelled {});
+
+            true
+        }
+
+        fn set_owners_and_threshold(
+            &mut self,
+            owners_to_add: Vec<AccountId>,
+            owners_to_remove: Vec<AccountId>,
+            threshold: u8,
+        ) -> Result<(), MultisigError> {
+            // Compute the final owners list
+            let mut owners_list = self.owners_list.clone();
+            for owner in owners_to_remove.iter() {
+                let position = owners_list
+                    .iter()
+                    .position(|x| x == owner)
+                    .ok_or(MultisigError::NotOwner)?;
+                owners_list.remove(position);
+            }
+            for owner in owners_to_add.iter() {
+                if owners_list.contains(owner) {
+                    return Err(MultisigError::OwnerAlreadyExists);
+                }
+                owners_list.push(*owner);
+            }
+
+            // Check that the final state is valid
+            if owners_list.len() > MAX_OWNERS as usize {
+                return Err(MultisigError::MaxOwnersReached);
+            }
+            ensure_creation_params(threshold, &owners_list)?;
+
+            // Apply the changes
+            self.owners_list = owners_list;
+            for owner in owners_to_remove.iter() {
+                self.owners_last_activity.retain(|(x, _)| x != owner);
+                self.remove_delegation(*owner);
+            }
+            for owner in owners_to_add.iter() {
+                self.assign_owner_slot(*owner)?;
+                self.remove_delegation(*owner);
+            }
+            self.threshold = threshold;
+
+            // emit event
+            Self::env().emit_event(ConfigChanged {
+                owners_added: owners_to_add,
+                owners_removed: owners_to_remove,
+                threshold,
+            });
+
+            Ok(())
+        }
+
+        fn push_active_tx(&mut self, tx_id: TxId) {
+            let position = self.active_txs_count;
+            self.active_txs.insert(position, &tx_id);
+            self.active_txs_positions.insert(tx_id, &position);
+            self.active_txs_count = position.saturating_add(1);
+        }
+
+        fn swap_remove_active_tx(&mut self, tx_id: TxId) {
+            let position = match self.active_txs_positions.get(tx_id) {
+                Some(position) => position,
+                None => return,
+            };
+            self.active_txs_positions.remove(tx_id);
+
+            // Move the last transaction to the freed position
+            let last_position = self.active_txs_count.saturating_sub(1);
+            if position != last_position {
+                let last_tx_id = self.active_txs.get(last_position).expect(
+                    "This should never fail. Every position below the count holds a transaction",
+                );
+                self.active_txs.insert(position, &last_tx_id);
+                self.active_txs_positions.insert(last_tx_id, &position);
+            }
+            self.active_txs.remove(last_position);
+            self.active_txs_count = last_position;
+        }
+
+        fn active_txs_page(&self, offset: u32, limit: u32) -> Vec<TxId> {
+            let end = offset.saturating_add(limit).min(self.active_txs_count);
+            (offset..end)
+                .filter_map(|position| self.active_txs.get(position))
+                .collect()
+        }
+
+        fn approve(
+            &mut self,
+            tx_id: TxId,
+            owner: AccountId,
+            delegate: Option<AccountId>,
+        ) -> Result<(), MultisigError> {
+            self.record_vote(tx_id, owner, delegate, true);
+            Ok(())
+        }
+
+        fn reject(
+            &mut self,
+            tx_id: TxId,
+            owner: AccountId,
+            delegate: Option<AccountId>,
+        ) -> Result<(), MultisigError> {
+            self.record_vote(tx_id, owner, delegate, false);
+            Ok(())
+        }
+
+        fn record_vote(
+            &mut self,
+            tx_id: TxId,
+            owner: AccountId,
+            delegate: Option<AccountId>,
+            approval: bool,
+        ) {
+            let slot = self
+                .owner_slots
+                .get(owner)
+                .expect("This should never fail. Every owner holds a slot");
+
+            // The first vote of a transaction is the approval of its proposer
+            let mut votes = self.votes.get(tx_id).unwrap_or_else(|| TxVotes::new(owner));
+            votes.record(slot, approval);
+
+            // Both identities are kept when a delegate votes on behalf of the owner
+            if let Some(delegate) = delegate {
+                votes.delegated |= 1 << slot;
+                self.vote_delegates.insert((tx_id, slot), &delegate);
+            }
+            self.votes.insert(tx_id, &votes);
+        }
+
+        fn tx_vote(&self, tx_id: TxId, owner: AccountId) -> Option<bool> {
+            // Only the current holder of a slot has a mapping to it
+            let slot = self.owner_slots.get(owner)?;
+            self.votes.get(tx_id)?.vote(slot)
+        }
+
+        fn tx_voters(&self, tx_id: TxId) -> Vec<AccountId> {
+            self.votes
+                .get(tx_id)
+                .map(|votes| self.votes_by_account(&votes))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(voter, _)| voter)
+                .collect()
+        }
+
+        fn tx_summary_if_not_voted(&self, tx_id: TxId, slot: OwnerSlot) -> Option<TxSummary> {
+            // The votes record tells if the slot has voted without reading the transaction
+            let votes = self.votes.get(tx_id)?;
+            if votes.vote(slot).is_some() {
+                return None;
+            }
+
+            let tx = self.txs.get(tx_id)?;
+            Some(TxSummary {
+                tx_id,
+                address: tx.address,
+                selector: tx.selector,
+                transferred_value: tx.transferred_value,
+                proposer: votes.proposer,
+                approvals: votes.approvals_count,
+                rejections: votes.rejections_count,
+                threshold: self.tx_threshold(&tx),
+            })
+        }
+
+        fn tx_status(&self, tx_id: TxId) -> Option<TxStatus> {
+            let tx = self.txs.get(tx_id)?;
+            let votes = self.votes.get(tx_id)?;
+            Some(self.status_of(tx_id, tx, votes))
+        }
+
+        fn status_of(&self, tx_id: TxId, tx: Transaction, votes: TxVotes) -> TxStatus {
+            let threshold = self.tx_threshold(&tx);
+
+            TxStatus {
+                proposer: votes.proposer,
+                approvals: votes.approvals_count,
+                rejections: votes.rejections_count,
+                votes: self.votes_by_account(&votes),
+                delegated_votes: self.delegated_votes(tx_id, &votes),
+                executable: votes.approvals_count >= threshold,
+                threshold,
+                tx,
+            }
+        }
+
+        fn matches_pending_filter(
+            &self,
+            tx: &Transaction,
+            votes: &TxVotes,
+            filter: &PendingTxsFilter,
+        ) -> bool {
+            if let Some(proposer) = filter.proposer {
+                if votes.proposer != proposer {
+                    return false;
+                }
+            }
+            if let Some(target) = filter.target {
+                if tx.call_type != CallType::Call || tx.address != target {
+                    return false;
+                }
+            }
+            if let Some(owner) = filter.awaiting_vote_of {
+                // Only the current holder of a slot has a mapping to it
+                let voted = self
+                    .owner_slots
+                    .get(owner)
+                    .and_then(|slot| votes.vote(slot))
+                    .is_some();
+                if voted {
+                    return false;
+                }
+            }
+            true
+        }
+
+        fn votes_by_account(&self, votes: &TxVotes) -> Vec<(AccountId, bool)> {
+            // Only the slots that voted are read from storage
+            voter_slots(votes.voters())
+                .filter_map(|slot| Some((self.slot_owners.get(slot)?, votes.vote(slot)?)))
+                .collect()
+        }
+
+        fn delegated_votes(&self, tx_id: TxId, votes: &TxVotes) -> Vec<(AccountId, AccountId)> {
+            voter_slots(votes.delegated)
+                .filter_map(|slot| {
+                    Some((
+                        self.slot_owners.get(slot)?,
+                        self.vote_delegates.get((tx_id, slot))?,
+                    ))
+                })
+                .collect()
+        }
+
+        fn assign_owner_slot(&mut self, owner: AccountId) -> Result<(), MultisigError> {
+            // A former owner gets its slot back if nobody else holds it
+            if let Some(slot) = self.owner_slots.get(owner) {
+                if self.slot_owners.get(slot) == Some(owner) {
+                    return Ok(());
+                }
+            }
+
+            // The slots of the current owners and of the voters of the active transactions are taken,
+            // so the votes of a former owner are never attributed to a new one
+            let mut taken = self
+                .active_txs_page(0, self.active_txs_count)
+                .iter()
+                .fold(0, |taken, tx_id| {
+                    taken | self.votes.get(tx_id).map_or(0, |votes| votes.voters())
+                });
+            for current_owner in self.owners_list.iter() {
+                if let Some(slot) = self.owner_slots.get(current_owner) {
+                    taken |= 1 << slot;
+                }
+            }
+
+            let slot = (0..MAX_OWNER_SLOTS)
+                .find(|slot| taken & (1 << slot) == 0)
+                .ok_or(MultisigError::NoOwnerSlotAvailable)?;
+
+            // The former owner that held the slot loses it
+            if let Some(former_owner) = self.slot_owners.get(slot) {
+                self.owner_slots.remove(former_owner);
+            }
+            self.owner_slots.insert(owner, &slot);
+            self.slot_owners.insert(slot, &owner);
+
+            Ok(())
+        }
+
+        //-------------------------------------------------------
+        // Read functions
+        //-------------------------------------------------------
+
+        /// Owners
+        /// Get Owners
+        /// The owners list is a list of account ids that can propose, approve or reject transactions
+        #[ink(message)]
+        pub fn get_owners(&self) -> Vec<AccountId> {
+            self.owners_list.clone()
+        }
+
+        /// Is owner
+        /// The parameter of the transaction is the owner's account id
+        /// The owner is checked if it is an owner
+        #[ink(message)]
+        pub fn is_owner(&self, owner: AccountId) -> bool {
+            self.owners_list.contains(&owner)
+        }
+
+        /// Treshold
+        /// Get Threshold
+        /// The threshold is the current minimum number of approvals required to execute a transaction
+        #[ink(message)]
+        pub fn get_threshold(&self) -> u8 {
+            self.threshold
+        }
+
+        /// Get Threshold Ratio
+        /// Returns the ratio of the owners the threshold is computed from or None if the threshold is fixed
+        #[ink(message)]
+        pub fn get_threshold_ratio(&self) -> Option<ThresholdRatio> {
+            self.threshold_ratio.clone()
+        }
+
+        /// Get Admin Threshold
+        /// The admin threshold is the minimum number of approvals required to execute an admin self call
+        /// It is the threshold unless an admin threshold has been set
+        #[ink(message)]
+        pub fn get_admin_threshold(&self) -> u8 {
+            let owners_count = self.owners_list.len() as u8;
+            self.admin_threshold
+                .unwrap_or(self.threshold)
+                .min(owners_count)
+        }
+
+        /// Get Rejection Threshold
+        /// The rejection threshold is the number of rejections that cancel a transaction
+        /// Returns None if transactions are only cancelled when their threshold cannot be met
+        #[ink(message)]
+        pub fn get_rejection_threshold(&self) -> Option<u8> {
+            self.rejection_threshold
+        }
+
+        /// Get Threshold Policy
+        /// The parameters of the transaction are the contract address and the selector
+        /// Returns the value ranges of the policy or None if there is no policy
+        #[ink(message)]
+        pub fn get_threshold_policy(
+            &self,
+            address: AccountId,
+            selector: [u8; 4],
+        ) -> Option<Vec<ThresholdPolicyRange>> {
+            self.policy(address, selector).cloned()
+        }
+
+        /// Get Transaction Threshold
+        /// The parameter of the transaction is the transaction id
+        /// Returns the number of approvals required to execute the transaction or None if the transaction id is not valid
+        #[ink(message)]
+        pub fn get_tx_threshold(&self, tx_id: TxId) -> Option<u8> {
+            self.txs.get(tx_id).map(|tx| self.tx_threshold(&tx))
+        }
+
+        /// Destinations
+        /// Is Destination Allowlist Enabled
+        /// Returns whether proposals are only accepted for the destinations in the allowlist
+        #[ink(message)]
+        pub fn is_destination_allowlist_enabled(&self) -> bool {
+            self.destination_allowlist_enabled
+        }
+
+        /// Is Destination Allowed
+        /// The parameter of the transaction is the destination's account id
+        /// Returns whether a transaction to the destination can be proposed and executed
+        #[ink(message)]
+        pub fn is_destination_allowed(&self, destination: AccountId) -> bool {
+            !self.destination_allowlist_enabled
+                || destination == self.env().account_id()
+                || self.allowed_destinations.contains(&destination)
+        }
+
+        /// Get Denied Selector Minimum Threshold
+        /// The parameter of the transaction is the selector
+        /// Returns the minimum threshold a transaction calling the selector must require or None if it is not denied
+        /// It is capped to the number of owners, like the thresholds of the transactions
+        #[ink(message)]
+        pub fn get_denied_selector(&self, selector: [u8; 4]) -> Option<u8> {
+            let owners_count = self.owners_list.len() as u8;
+            self.denied_selectors
+                .iter()
+                .find(|(denied, _)| *denied == selector)
+                .map(|(_, min_threshold)| (*min_threshold).min(owners_count))
+        }
+
+        /// Delegate calls
+        /// Get Storage Layout Version
+        /// Returns the storage layout version that delegate call scripts must be written against
+        #[ink(message)]
+        pub fn get_storage_layout_version(&self) -> u32 {
+            STORAGE_LAYOUT_VERSION
+        }
+
+        /// Get Delegate Code Hash Layout Version
+        /// The parameter of the transaction is the code hash
+        /// Returns the storage layout version of the code hash or None if it is not allowed to be delegate called
+        #[ink(message)]
+        pub fn get_delegate_code_hash(&self, code_hash: Hash) -> Option<u32> {
+            self.delegate_code_hashes
+                .iter()
+                .find(|(hash, _)| *hash == code_hash)
+                .map(|(_, layout_version)| *layout_version)
+        }
+
+        /// Emergency pause
+        /// Is Paused
+        /// Returns true if the wallet is paused and the pause has not expired yet
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            matches!(self.paused_until, Some(until) if self.env().block_number() < until)
+        }
+
+        /// Get Paused Until
+        /// Returns the block number at which the last pause expires or was lifted, or None if the wallet has
+        /// never been paused
+        #[ink(message)]
+        pub fn get_paused_until(&self) -> Option<BlockNumber> {
+            self.paused_until
+        }
+
+        /// Get Pause Guardian
+        /// Returns the account that can pause the wallet besides the owners or None if there is no pause guardian
+        #[ink(message)]
+        pub fn get_pause_guardian(&self) -> Option<AccountId> {
+            self.pause_guardian
+        }
+
+        /// Get Pause Duration
+        /// Returns the number of blocks a pause lasts
+        #[ink(message)]
+        pub fn get_pause_duration(&self) -> BlockNumber {
+            self.pause_duration
+        }
+
+        /// Inheritance
+        /// Get Inheritance Config
+        /// Returns the inheritance configuration or None if the inheritance is disabled
+        #[ink(message)]
+        pub fn get_inheritance_config(&self) -> Option<InheritanceConfig> {
+            self.inheritance_config.clone()
+        }
+
+        /// Get Inheritance Claimable At
+        /// Returns the block number from which the inheritance can be claimed if no owner is active before,
+        /// or None if the inheritance is disabled
+        #[ink(message)]
+        pub fn get_inheritance_claimable_at(&self) -> Option<BlockNumber> {
+            self.inheritance_config
+                .as_ref()
+                .map(|config| self.inheritance_claimable_at(config))
+        }
+
+        /// Get Last Activity
+        /// Returns the block number of the last owner activity in the wallet
+        #[ink(message)]
+        pub fn get_last_activity(&self) -> BlockNumber {
+            self.last_activity
+        }
+
+        /// Get Owner Last Activity
+        /// The parameter of the transaction is the owner's account id
+        /// Returns the block number of the last activity of the owner or None if the owner has not been active
+        #[ink(message)]
+        pub fn get_owner_last_activity(&self, owner: AccountId) -> Option<BlockNumber> {
+            self.owners_last_activity
+                .iter()
+                .find(|(x, _)| *x == owner)
+                .map(|(_, last_activity)| *last_activity)
+        }
+
+        /// Vote delegation
+        /// Get Delegation
+        /// The parameter of the transaction is the owner's account id
+        /// Returns the delegation of the owner, expired or not, or None if the owner has not delegated its votes
+        #[ink(message)]
+        pub fn get_delegation(&self, owner: AccountId) -> Option<Delegation> {
+            self.delegations.get(owner)
+        }
+
+        /// Get Delegator
+        /// The parameter of the transaction is the delegate's account id
+        /// Returns the owner the delegate currently votes on behalf of or None if it has no active delegation
+        #[ink(message)]
+        pub fn get_delegator(&self, delegate: AccountId) -> Option<AccountId> {
+            self.active_delegator(delegate)
+        }
+
+        /// Nested multisigs
+        /// Get Pending Outer Txs
+        /// The parameter of the transaction is the outer multisig
+        /// Returns the transactions of the outer multisig that this multisig has not voted yet,
+        /// empty if this multisig is not an owner of it
+        #[ink(message)]
+        pub fn get_pending_outer_txs(
+            &self,
+            outer: AccountId,
+        ) -> Result<Vec<TxSummary>, MultisigError> {
+            let call_result = build_call::<<Self as ::ink::env::ContractEnv>::Env>()
+                .call(outer)
+                .exec_input(
+                    ExecutionInput::new(GET_TXS_AWAITING_VOTE_SELECTOR.into())
+                        .push_arg(self.env().account_id()),
+                )
+                .returns::<Vec<TxSummary>>()
+                .try_invoke();
+
+            match call_result {
+                Ok(Ok(txs)) => Ok(txs),
+                Ok(Err(e)) => Err(MultisigError::LangExecutionFailed(e)),
+                Err(e) => Err(MultisigError::from(e)),
+            }
+        }
+
+        /// Social recovery
+        /// Get Recovery Config
+        /// Returns the social recovery configuration or None if the social recovery is disabled
+        #[ink(message)]
+        pub fn get_recovery_config(&self) -> Option<RecoveryConfig> {
+            self.recovery_config.clone()
+        }
+
+        /// Get Pending Recovery
+        /// Returns the recovery proposed by the guardians or None if there is no pending recovery or it has expired
+        #[ink(message)]
+        pub fn get_pending_recovery(&self) -> Option<Recovery> {
+            self.recovery
+                .clone()
+                .filter(|recovery| !self.is_recovery_expired(recovery))
+        }
+
+        /// Staking
+        /// Get Staking Pallets
+        /// Returns the indices of the staking pallets or None if they have not been configured
+        #[ink(message)]
+        pub fn get_staking_pallets(&self) -> Option<StakingPallets> {
+            self.staking_pallets
+        }
+
+        /// Get Staking Records Count
+        /// Returns the number of staking operations performed by the multisig
+        #[ink(message)]
+        pub fn get_staking_records_count(&self) -> u32 {
+            self.staking_records_count
+        }
+
+        /// Get Staking Record
+        /// The parameter of the transaction is the index of the staking record
+        /// Returns the staking operation performed with its block number and timestamp or None if it does not exist
+        #[ink(message)]
+        pub fn get_staking_record(&self, index: u32) -> Option<StakingRecord> {
+            self.staking_records.get(index)
+        }
+
+        /// Get Staking Records
+        /// The parameters of the transaction are the index of the first record and the maximum number of records
+        /// Returns the staking records in the order they were performed
+        /// The maximum number of records returned is defined by MAX_STAKING_RECORDS_PAGE
+        #[ink(message)]
+        pub fn get_staking_records(&self, offset: u32, limit: u32) -> Vec<StakingRecord> {
+            let end = offset
+                .saturating_add(limit.min(MAX_STAKING_RECORDS_PAGE))
+                .min(self.staking_records_count);
+            (offset..end)
+                .filter_map(|index| self.staking_records.get(index))
+                .collect()
+        }
+
+        /// Guard
+        /// Get Guard
+        /// Returns the guard contract that checks every transaction execution or None if there is no guard
+        #[ink(message)]
+        pub fn get_guard(&self) -> Option<AccountId> {
+            self.guard
+        }
+
+        /// Modules
+        /// Get Modules
+        /// The modules list is a list of contracts that can execute transactions without collecting approvals
+        #[ink(message)]
+        pub fn get_modules(&self) -> Vec<AccountId> {
+            self.modules_list.clone()
+        }
+
+        /// Is module
+        /// The parameter of the transaction is the module's account id
+        /// The module is checked if it is enabled
+        #[ink(message)]
+        pub fn is_module(&self, module: AccountId) -> bool {
+            self.modules_list.contains(&module)
+        }
+
+        /// Transactions
+        /// Get Next Transaction Id
+        /// Returns the next transaction id
+        #[ink(message)]
+        pub fn get_next_tx_id(&self) -> TxId {
+            self.next_tx_id
+        }
+
+        /// Get Active Transactions Id List
+        /// Returns the list of active transactions
+        /// The order is the proposal order until a transaction is removed, when the last one takes its position
+        #[ink(message)]
+        pub fn get_active_txid_list(&self) -> Vec<TxId> {
+            self.active_txs_page(0, self.active_txs_count)
+        }
+
+        /// Get Active Transactions Ids
+        /// The parameters of the transaction are the position of the first transaction and the maximum number of transactions
+        /// Returns the active transaction ids in the same order as get_active_txid_list
+        /// The maximum number of transaction ids returned is defined by MAX_TXS_PAGE
+        #[ink(message)]
+        pub fn get_active_txids(&self, offset: u32, limit: u32) -> Vec<TxId> {
+            self.active_txs_page(offset, limit.min(MAX_TXS_PAGE))
+        }
+
+        /// Get Active Transactions Count
+        /// Returns the number of active transactions
+        #[ink(message)]
+        pub fn get_active_txs_count(&self) -> u32 {
+            self.active_txs_count
+        }
+
+        /// Get Transaction
+        /// The parameter of the transaction is the transaction id
+        /// Returns the transaction or None if the transaction id is not valid
+        #[ink(message)]
+        pub fn get_tx(&self, index: TxId) -> Option<Transaction> {
+            self.txs.get(index)
+        }
+
+        /// Is Transaction Valid
+        /// The parameter of the transaction is the transaction id
+        /// Returns a result with () if the transaction id is valid or an Error if it is not valid
+        #[ink(message)]
+        pub fn is_tx_valid(&self, tx_id: TxId) -> Result<(), MultisigError> {
+            // Only the transactions in the active ones are valid
+            self.active_txs_positions
+                .get(tx_id)
+                .filter(|&position| {
+                    position < self.active_txs_count && self.active_txs.get(position) == Some(tx_id)
+                })
+                .map(|_| ())
+                .ok_or(MultisigError::InvalidTxId)
+        }
+
+        /// Get Transaction Approvals
+        /// The parameter of the transaction is the transaction id
+        /// Returns the number of approvals for the transaction if the transaction id is valid or None if it is not valid
+        #[ink(message)]
+        pub fn get_tx_approvals(&self, tx_id: TxId) -> Option<u8> {
+            self.votes.get(tx_id).map(|votes| votes.approvals_count)
+        }
+
+        /// Get Transaction Rejections
+        /// The parameter of the transaction is the transaction id
+        /// Returns the number of rejections for the transaction if the transaction id is valid or None if it is not valid
+        #[ink(message)]
+        pub fn get_tx_rejections(&self, tx_id: TxId) -> Option<u8> {
+            self.votes.get(tx_id).map(|votes| votes.rejections_count)
+        }
+
+        /// Get Transaction Approval For Account
+        /// The parameters of the transaction are the transaction id and the account id
+        /// Returns true if the account has approved the transaction, false if the account has rejected the transaction or None if the transaction id is not valid
+        #[ink(message)]
+        pub fn get_tx_approval_for_account(&self, tx_id: TxId, owner: AccountId) -> Option<bool> {
+            self.tx_vote(tx_id, owner)
+        }
+
+        /// Get Transaction Status
+        /// The parameter of the transaction is the transaction id
+        /// Returns the transaction with its proposer, votes, threshold and whether it can be executed,
+        /// or None if the transaction id is not valid
+        #[ink(message)]
+        pub fn get_tx_status(&self, tx_id: TxId) -> Option<TxStatus> {
+            self.tx_status(tx_id)
+        }
+
+        /// Get Pending Transactions
+        /// The parameters of the transaction are the position of the first transaction in get_active_txid_list,
+        /// the maximum number of active transactions to check and the filters they must match
+        /// Returns the matching transactions of the page with their id and status, in the same order as get_active_txid_list
+        /// A page can have fewer matches than its size, the next page starts at the position plus the size
+        /// The maximum number of transactions checked is defined by MAX_TXS_PAGE
+        #[ink(message)]
+        pub fn get_pending_txs(
+            &self,
+            offset: u32,
+            limit: u32,
+            filter: PendingTxsFilter,
+        ) -> Vec<(TxId, TxStatus)> {
+            // Only owners can have transactions awaiting their vote
+            if matches!(filter.awaiting_vote_of, Some(owner) if !self.owners_list.contains(&owner))
+            {
+                return Vec::new();
+            }
+
+            // The status, which reads every vote, is only built for the matches of the page
+            self.active_txs_page(offset, limit.min(MAX_TXS_PAGE))
+                .into_iter()
+                .filter_map(|tx_id| Some((tx_id, self.txs.get(tx_id)?, self.votes.get(tx_id)?)))
+                .filter(|(_, tx, votes)| self.matches_pending_filter(tx, votes, &filter))
+                .map(|(tx_id, tx, votes)| (tx_id, self.status_of(tx_id, tx, votes)))
+                .collect()
+        }
+
+        /// Get Transactions Awaiting Vote
+        /// The parameter of the transaction is the owner's account id
+        /// Returns the summaries of the active transactions the owner has not approved or rejected yet,
+        /// in the same order as get_active_txid_list, or an empty list if the account is not an owner
+        /// Only one storage read is needed per transaction the owner has already voted
+        #[ink(message)]
+        pub fn get_txs_awaiting_vote(&self, owner: AccountId) -> Vec<TxSummary> {
+            let slot = match self.owner_slots.get(owner) {
+                Some(slot) if self.owners_list.contains(&owner) => slot,
+                _ => return Vec::new(),
+            };
+
+            self.active_txs_page(0, self.active_txs_count)
+                .into_iter()
+                .filter_map(|tx_id| self.tx_summary_if_not_voted(tx_id, slot))
+                .collect()
+        }
+
+        /// Get Transaction Voters
+        /// The parameter of the transaction is the transaction id
+        /// Returns the accounts that have approved or rejected the transaction, including the ones
+        /// that are not owners anymore, or None if the transaction id is not valid
+        #[ink(message)]
+        pub fn get_tx_voters(&self, tx_id: TxId) -> Option<Vec<AccountId>> {
+            self.is_tx_valid(tx_id).ok().map(|_| self.tx_voters(tx_id))
+        }
+    }
+
+    // Dispatch a SCALE encoded RuntimeCall on behalf of the contract
+    #[cfg(not(test))]
+    fn dispatch_runtime_call(call: &[u8]) -> Result<(), EnvError> {
+        ink::env::call_runtime::<Environment, _>(&InputArgs(call))
+    }
+
+    // The off-chain environment can't dispatch runtime calls, so the unit tests use a mocked runtime
+    #[cfg(test)]
+    use self::tests::mocked_runtime::dispatch as dispatch_runtime_call;
+
+    // Delegate call a code hash with the given selector and raw input
+    #[cfg(not(test))]
+    fn delegate_call_code<R: scale::Decode>(
+        code_hash: Hash,
+        selector: [u8; 4],
+        input: &[u8],
+        allow_reentry: bool,
+    ) -> Result<ink::MessageResult<R>, EnvError> {
+        build_call::<Environment>()
+            .delegate(code_hash)
+            .call_flags(reentry_flags(allow_reentry))
+            .exec_input(ExecutionInput::new(selector.into()).push_arg(InputArgs(input)))
+            .returns::<R>()
+            .try_invoke()
+    }
+
+    #[cfg(not(test))]
+    fn own_code_hash() -> Hash {
+        ink::env::own_code_hash::<Environment>()
+            .expect("This should never fail. The multisig always has a code hash")
+    }
+
+    // The off-chain environment can't perform delegate calls, so the unit tests use mocked scripts
+    #[cfg(test)]
+    use self::tests::mocked_scripts::{delegate_call as delegate_call_code, own_code_hash};
+
+    // Call the destination of the transaction with its selector, input and limits
+    #[cfg(not(test))]
+    fn call_contract(tx: &Transaction) -> Result<ink::MessageResult<OutputBytes>, EnvError> {
+        let mut call = build_call::<Environment>()
+            .call(tx.address)
+            .ref_time_limit(tx.ref_time_limit)
+            .proof_size_limit(tx.proof_size_limit)
+            .transferred_value(tx.transferred_value)
+            .call_flags(reentry_flags(tx.allow_reentry))
+            .exec_input(ExecutionInput::new(tx.selector.into()).push_arg(InputArgs(&tx.input)))
+            .returns::<OutputBytes>();
+
+        // Without a storage deposit limit the call can use up to the limit of the execution
+        if let Some(limit) = tx.storage_deposit_limit {
+            call = call.storage_deposit_limit(limit);
+        }
+        call.try_invoke()
+    }
+
+    // The off-chain environment can't call contracts, so the unit tests use mocked calls
+    #[cfg(test)]
+    use self::tests::mocked_calls::call as call_contract;
+
+    // Instantiate the code hash with the constructor selector, input and limits of the transaction
+    // The error of a fallible constructor is kept encoded, since its type is unknown
+    #[cfg(not(test))]
+    fn instantiate_contract(
+        code_hash: Hash,
+        salt: &[u8],
+        tx: &Transaction,
+    ) -> Result<ink::ConstructorResult<Result<InstantiatedContract, OutputBytes>>, EnvError> {
+        let mut create = ink::env::call::build_create::<InstantiatedContract>()
+            .code_hash(code_hash)
+            .ref_time_limit(tx.ref_time_limit)
+            .proof_size_limit(tx.proof_size_limit)
+            .endowment(tx.transferred_value)
+            .exec_input(ExecutionInput::new(tx.selector.into()).push_arg(InputArgs(&tx.input)))
+            .salt_bytes(salt)
+            .returns::<Result<InstantiatedContract, OutputBytes>>();
+
+        // Without a storage deposit limit the instantiation can use up to the limit of the execution
+        if let Some(limit) = tx.storage_deposit_limit {
+            create = create.storage_deposit_limit(limit);
+        }
+        create.try_instantiate()
+    }
+
+    // The off-chain environment can't instantiate contracts, so the unit tests use a mocked instantiation
+    #[cfg(test)]
+    use self::tests::mocked_instantiation::instantiate as instantiate_contract;
+
+    #[cfg(not(test))]
+    use ink::env::return_value;
+
+    #[cfg(not(test))]
+    fn gas_left() -> u64 {
+        ink::env::gas_left::<Environment>()
+    }
+
+    // The off-chain environment can't meter the gas or return from the execution,
+    // so the unit tests use a mocked gas meter and catch a mocked return
+    #[cfg(test)]
+    use self::tests::mocked_execution::{gas_left, return_value};
+
+    // Slots set in a voters bitmap, in ascending order
+    fn voter_slots(voters: Voters) -> impl Iterator<Item = OwnerSlot> {
+        let mut remaining = voters;
+        core::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            let slot = remaining.trailing_zeros() as OwnerSlot;
+            // Clear the lowest set bit
+            remaining &= remaining.wrapping_sub(1);
+            Some(slot)
+        })
+    }
+
+    // Flags of a call performed by the multisig
+    #[cfg(not(test))]
+    fn reentry_flags(allow_reentry: bool) -> CallFlags {
+        if allow_reentry {
+            CallFlags::ALLOW_REENTRY
+        } else {
+            CallFlags::empty()
+        }
+    }
+
+    // Any failure of the guard call, a revert included, is considered a rejection
+    fn guard_approves(guard_result: Result<ink::MessageResult<bool>, EnvError>) -> bool {
+        matches!(guard_result, Ok(Ok(true)))
+    }
+
+    // Ask the guard to check a transaction before it is performed
+    #[cfg(not(test))]
+    fn guard_check_transaction(
+        guard: AccountId,
+        tx_id: TxId,
+        tx: &Transaction,
+    ) -> Result<ink::MessageResult<bool>, EnvError> {
+        build_call::<Environment>()
+            .call(guard)
+            .exec_input(
+                ExecutionInput::new(GUARD_CHECK_TRANSACTION_SELECTOR.into())
+                    .push_arg(tx_id)
+                    .push_arg(tx),
+            )
+            .returns::<bool>()
+            .try_invoke()
+    }
+
+    // Ask the guard to check the outcome of a transaction after it is performed
+    #[cfg(not(test))]
+    fn guard_check_after_execution(
+        guard: AccountId,
+        tx_id: TxId,
+        success: bool,
+    ) -> Result<ink::MessageResult<bool>, EnvError> {
+        build_call::<Environment>()
+            .call(guard)
+            .exec_input(
+                ExecutionInput::new(GUARD_CHECK_AFTER_EXECUTION_SELECTOR.into())
+                    .push_arg(tx_id)
+                    .push_arg(success),
+            )
+            .returns::<bool>()
+            .try_invoke()
+    }
+
+    // Perform a transaction checked by the guard in a nested call of the multisig to itself
+    // Every change of a failed nested call is reverted, so its failure is the failure of the transaction
+    #[cfg(not(test))]
+    fn call_perform_guarded_tx(multisig: AccountId, tx_id: TxId, tx: &Transaction) -> TxResult {
+        let call_result = build_call::<Environment>()
+            .call(multisig)
+            .call_flags(CallFlags::ALLOW_REENTRY)
+            .exec_input(
+                ExecutionInput::new(PERFORM_GUARDED_TX_SELECTOR.into())
+                    .push_arg(tx_id)
+                    .push_arg(tx),
+            )
+            .returns::<Result<TxResult, MultisigError>>()
+            .try_invoke();
+
+        match call_result {
+            Ok(Ok(result)) => result.unwrap_or_else(TxResult::Failed),
+            Ok(Err(e)) => TxResult::Failed(MultisigError::LangExecutionFailed(e)),
+            Err(e) => TxResult::Failed(MultisigError::from(e)),
+        }
+    }
+
+    // The off-chain environment can't call other contracts, so the unit tests use a mocked guard
+    #[cfg(test)]
+    use self::tests::mocked_guard::{
+        call_perform_guarded_tx, check_after_execution as guard_check_after_execution,
+        check_transaction as guard_check_transaction,
+    };
 
     // Ensure the params of the constructor are valid
     // according to the rules of the contract
     fn ensure_creation_params(
         threshold: u8,
-        owners_list: &Vec<AccountId>,
+        owners_list: &[AccountId],
     ) -> Result<(), MultisigError> {
         // Check that owners are not empty
         if owners_list.is_empty() {
             return Err(MultisigError::OwnersCantBeEmpty);
         }
 
-        // Check that threshold is not greater than owners
-        if threshold > owners_list.len() as u8 {
-            return Err(MultisigError::ThresholdGreaterThanOwners);
+        ensure_threshold(threshold, owners_list.len())
+    }
+
+    // Ensure the guardians and quorum of the social recovery are valid
+    fn ensure_recovery_config(config: &RecoveryConfig) -> Result<(), MultisigError> {
+        let mut guardians = config.guardians.clone();
+        guardians.sort_unstable();
+        guardians.dedup();
+
+        // Check that the guardians are not empty, duplicated or too many
+        if guardians.is_empty()
+            || guardians.len() != config.guardians.len()
+            || guardians.len() > MAX_GUARDIANS as usize
+        {
+            return Err(MultisigError::InvalidRecoveryConfig);
+        }
+
+        // Check that the quorum can be reached
+        if config.quorum == 0 || config.quorum as usize > guardians.len() {
+            return Err(MultisigError::InvalidRecoveryConfig);
+        }
+
+        // Check that the owners have time to cancel a recovery
+        if config.delay < MIN_RECOVERY_DELAY {
+            return Err(MultisigError::InvalidRecoveryConfig);
+        }
+
+        Ok(())
+    }
+
+    // Ensure the beneficiaries, threshold and inactivity period of the inheritance are valid
+    // Returns the configuration without duplicated beneficiaries
+    fn ensure_inheritance_config(
+        config: InheritanceConfig,
+    ) -> Result<InheritanceConfig, MultisigError> {
+        let mut config = config;
+        config.beneficiaries.sort_unstable();
+        config.beneficiaries.dedup();
+
+        // Check that there are beneficiaries and that the period is not zero
+        if config.beneficiaries.is_empty() || config.inactivity_period == 0 {
+            return Err(MultisigError::InvalidInheritanceConfig);
+        }
+
+        // Check that the beneficiaries can be owners
+        if let InheritanceAction::TakeControl(threshold) = config.action {
+            if config.beneficiaries.len() > MAX_OWNERS as usize {
+                return Err(MultisigError::InvalidInheritanceConfig);
+            }
+            ensure_threshold(threshold, config.beneficiaries.len())
+                .map_err(|_| MultisigError::InvalidInheritanceConfig)?;
+        }
+
+        Ok(config)
+    }
+
+    // Ensure a threshold ratio is valid for the given number of owners
+    fn ensure_threshold_ratio(
+        threshold_ratio: &ThresholdRatio,
+        owners_count: usize,
+    ) -> Result<(), MultisigError> {
+        // Check that the ratio is between 0 and 1 and the minimum threshold is not zero
+        if threshold_ratio.numerator == 0
+            || threshold_ratio.numerator > threshold_ratio.denominator
+            || threshold_ratio.min_threshold == 0
+        {
+            return Err(MultisigError::InvalidThresholdRatio);
+        }
+
+        ensure_threshold(threshold_ratio.threshold(owners_count), owners_count)
+    }
+
+    // Ensure a threshold is valid for the given number of owners
+    fn ensure_threshold(threshold: u8, owners_count: usize) -> Result<(), MultisigError> {
+        // Check that threshold is not greater than owners
+        if threshold as usize > owners_count {
+            return Err(MultisigError::ThresholdGreaterThanOwners);
+        }
+
+        // Check that threshold is not zero
+        if threshold == 0 {
+            return Err(MultisigError::ThresholdCantBeZero);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test::{
+            callee, count_used_storage_cells, default_accounts, get_account_balance,
+            recorded_events, set_account_balance, set_block_timestamp, set_callee, set_caller,
+            DefaultAccounts,
+        };
+        use scale::{Decode, Encode};
+
+        /// Runtime that records the dispatched calls instead of dispatching them
+        pub mod mocked_runtime {
+            use super::*;
+            use std::cell::RefCell;
+
+            thread_local! {
+                static DISPATCHED: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+                static FAILING: RefCell<bool> = const { RefCell::new(false) };
+            }
+
+            pub fn dispatch(call: &[u8]) -> Result<(), EnvError> {
+                DISPATCHED.with(|dispatched| dispatched.borrow_mut().push(call.to_vec()));
+                if FAILING.with(|failing| *failing.borrow()) {
+                    return Err(EnvError::ReturnError(
+                        ink::env::ReturnErrorCode::CallRuntimeFailed,
+                    ));
+                }
+                Ok(())
+            }
+
+            pub fn set_failing(failing: bool) {
+                FAILING.with(|f| *f.borrow_mut() = failing);
+            }
+
+            pub fn dispatched() -> Vec<Vec<u8>> {
+                DISPATCHED.with(|dispatched| dispatched.borrow().clone())
+            }
+
+            pub fn revert_to(count: usize) {
+                DISPATCHED.with(|dispatched| dispatched.borrow_mut().truncate(count));
+            }
+        }
+
+        /// Guard that answers the checks as configured, and records them with the nested calls
+        pub mod mocked_guard {
+            use super::*;
+            use std::cell::RefCell;
+
+            /// Replies of the guard to both checks, None if the guard call reverts
+            type Replies = (Option<bool>, Option<bool>);
+
+            thread_local! {
+                static REPLIES: RefCell<Replies> = const { RefCell::new((Some(true), Some(true))) };
+                static CALLS: RefCell<Vec<(TxId, [u8; 4])>> = const { RefCell::new(Vec::new()) };
+            }
+
+            pub fn check_transaction(
+                _guard: AccountId,
+                tx_id: TxId,
+                _tx: &Transaction,
+            ) -> Result<ink::MessageResult<bool>, EnvError> {
+                record(tx_id, GUARD_CHECK_TRANSACTION_SELECTOR);
+                reply(REPLIES.with(|replies| replies.borrow().0))
+            }
+
+            pub fn check_after_execution(
+                _guard: AccountId,
+                tx_id: TxId,
+                _success: bool,
+            ) -> Result<ink::MessageResult<bool>, EnvError> {
+                record(tx_id, GUARD_CHECK_AFTER_EXECUTION_SELECTOR);
+                reply(REPLIES.with(|replies| replies.borrow().1))
+            }
+
+            /// Performs the nested call on the stored root, which is only written back if the
+            /// call succeeds, so a failed call is reverted as it is on chain
+            pub fn call_perform_guarded_tx(
+                multisig: AccountId,
+                tx_id: TxId,
+                tx: &Transaction,
+            ) -> TxResult {
+                let mut nested: MultiSig =
+                    ink::env::get_contract_storage(&<MultiSig as StorageKey>::KEY)
+                        .ok()
+                        .flatten()
+                        .expect("The root has been written");
+                let tx = Transaction::decode(&mut &tx.encode()[..]).expect("It was just encoded");
+                let caller = ink::env::caller::<Environment>();
+                let dispatched = mocked_runtime::dispatched().len();
+                record(tx_id, PERFORM_GUARDED_TX_SELECTOR);
+
+                set_caller::<Environment>(multisig);
+                let result = nested.perform_guarded_tx(tx_id, tx);
+                if result.is_ok() {
+                    ink::env::set_contract_storage(&<MultiSig as StorageKey>::KEY, &nested);
+                } else {
+                    mocked_runtime::revert_to(dispatched);
+                }
+                set_caller::<Environment>(caller);
+
+                result.unwrap_or_else(TxResult::Failed)
+            }
+
+            pub fn set_approving(before: bool, after: bool) {
+                set_replies(Some(before), Some(after));
+            }
+
+            pub fn set_replies(before: Option<bool>, after: Option<bool>) {
+                REPLIES.with(|replies| *replies.borrow_mut() = (before, after));
+            }
+
+            fn reply(approval: Option<bool>) -> Result<ink::MessageResult<bool>, EnvError> {
+                approval.map(Ok).ok_or(EnvError::ReturnError(
+                    ink::env::ReturnErrorCode::CalleeTrapped,
+                ))
+            }
+
+            pub fn calls() -> Vec<(TxId, [u8; 4])> {
+                CALLS.with(|calls| calls.borrow().clone())
+            }
+
+            fn record(tx_id: TxId, selector: [u8; 4]) {
+                CALLS.with(|calls| calls.borrow_mut().push((tx_id, selector)));
+            }
+        }
+
+        /// Gas meter and return from the execution, which unwinds with the flags and the encoded value
+        pub mod mocked_execution {
+            use super::*;
+            use std::cell::RefCell;
+
+            /// Gas consumed by the code between two readings of the gas left
+            pub const GAS_PER_READING: u64 = 1_000;
+
+            thread_local! {
+                static GAS_LEFT: RefCell<u64> = const { RefCell::new(u64::MAX) };
+            }
+
+            pub struct Returned {
+                pub flags: ReturnFlags,
+                pub value: Vec<u8>,
+            }
+
+            pub fn gas_left() -> u64 {
+                GAS_LEFT.with(|gas_left| {
+                    let left = *gas_left.borrow();
+                    *gas_left.borrow_mut() = left - GAS_PER_READING;
+                    left
+                })
+            }
+
+            pub fn return_value<R: scale::Encode>(flags: ReturnFlags, return_value: &R) -> ! {
+                std::panic::panic_any(Returned {
+                    flags,
+                    value: return_value.encode(),
+                })
+            }
+
+            /// Runs the message and returns what it returned from the execution with
+            pub fn returned(message: impl FnOnce()) -> Returned {
+                let unwound = std::panic::catch_unwind(std::panic::AssertUnwindSafe(message))
+                    .expect_err("The message returns from the execution");
+                *unwound
+                    .downcast::<Returned>()
+                    .unwrap_or_else(|_| panic!("The message panicked"))
+            }
+        }
+
+        /// Calls to the multisig itself, which run the message on the stored root as a reentrant call
+        pub mod mocked_calls {
+            use super::*;
+
+            /// Runs the self call message and writes the root back if it succeeds
+            /// Calls to other accounts succeed without output, as calls to accounts without code
+            pub fn call(tx: &Transaction) -> Result<ink::MessageResult<OutputBytes>, EnvError> {
+                let multisig = callee::<Environment>();
+                if tx.address != multisig {
+                    return Ok(Ok(OutputBytes(Vec::new())));
+                }
+                assert!(tx.allow_reentry, "Self calls are only allowed with reentry");
+
+                let mut reentrant: MultiSig =
+                    ink::env::get_contract_storage(&<MultiSig as StorageKey>::KEY)
+                        .ok()
+                        .flatten()
+                        .expect("The root has been written");
+                let caller = ink::env::caller::<Environment>();
+                set_caller::<Environment>(multisig);
+                const ADD_OWNER: [u8; 4] = ink::selector_bytes!("add_owner");
+                const REMOVE_OWNER: [u8; 4] = ink::selector_bytes!("remove_owner");
+                const CHANGE_THRESHOLD: [u8; 4] = ink::selector_bytes!("change_threshold");
+                let input = &mut &tx.input[..];
+                let result = match tx.selector {
+                    ADD_OWNER => {
+                        reentrant.add_owner(AccountId::decode(input).map_err(EnvError::Decode)?)
+                    }
+                    REMOVE_OWNER => {
+                        reentrant.remove_owner(AccountId::decode(input).map_err(EnvError::Decode)?)
+                    }
+                    CHANGE_THRESHOLD => {
+                        reentrant.change_threshold(u8::decode(input).map_err(EnvError::Decode)?)
+                    }
+                    _ => return Ok(Err(LangError::CouldNotReadInput)),
+                };
+                set_caller::<Environment>(caller);
+
+                // A failed message reverts its changes
+                if result.is_ok() {
+                    reentrant.write_root();
+                }
+                Ok(Ok(OutputBytes(result.encode())))
+            }
+        }
+
+        /// Instantiation whose outcome is decoded as the on-chain environment decodes it
+        pub mod mocked_instantiation {
+            use super::*;
+            use ink::env::call::ConstructorReturnType;
+            use std::cell::RefCell;
+
+            type Output = Result<InstantiatedContract, OutputBytes>;
+
+            thread_local! {
+                /// Account id of the next instantiated contract, or the output of its reverted constructor
+                static OUTCOME: RefCell<Result<AccountId, Vec<u8>>> =
+                    RefCell::new(Ok(AccountId::from([0x0c; 32])));
+            }
+
+            pub fn instantiate(
+                _code_hash: Hash,
+                _salt: &[u8],
+                _tx: &Transaction,
+            ) -> Result<ink::ConstructorResult<Output>, EnvError> {
+                let output = match OUTCOME.with(|outcome| outcome.borrow().clone()) {
+                    Ok(contract) => {
+                        return Ok(Ok(<Output as ConstructorReturnType<_>>::ok(
+                            InstantiatedContract(contract),
+                        )))
+                    }
+                    Err(output) => output,
+                };
+                match ink::ConstructorResult::<Result<(), OutputBytes>>::decode(&mut &output[..])
+                    .map_err(EnvError::Decode)?
+                {
+                    Ok(Err(error)) => Ok(Ok(<Output as ConstructorReturnType<_>>::err(error)
+                        .expect("The output is a result"))),
+                    Ok(Ok(())) => panic!("A reverted constructor returns an error"),
+                    Err(e) => Ok(Err(e)),
+                }
+            }
+
+            pub fn set_deployed(contract: AccountId) {
+                OUTCOME.with(|outcome| *outcome.borrow_mut() = Ok(contract));
+            }
+
+            pub fn set_reverted(output: Vec<u8>) {
+                OUTCOME.with(|outcome| *outcome.borrow_mut() = Err(output));
+            }
+        }
+
+        /// Scripts that run in the test instead of being delegate called
+        pub mod mocked_scripts {
+            use super::*;
+            use std::{cell::RefCell, rc::Rc};
+
+            type Script = Rc<dyn Fn([u8; 4], &[u8]) -> Vec<u8>>;
+
+            thread_local! {
+                static SCRIPTS: RefCell<Vec<(Hash, Script)>> = RefCell::new(Vec::new());
+                static CODE_HASH: RefCell<Hash> = RefCell::new(Hash::from([0x11; 32]));
+            }
+
+            /// Runs the script registered for the code hash, which returns the encoded result
+            pub fn delegate_call<R: scale::Decode>(
+                code_hash: Hash,
+                selector: [u8; 4],
+                input: &[u8],
+                _allow_reentry: bool,
+            ) -> Result<ink::MessageResult<R>, EnvError> {
+                let script = SCRIPTS
+                    .with(|scripts| {
+                        scripts
+                            .borrow()
+                            .iter()
+                            .find(|(hash, _)| *hash == code_hash)
+                            .map(|(_, script)| script.clone())
+                    })
+                    .ok_or(EnvError::ReturnError(
+                        ink::env::ReturnErrorCode::CodeNotFound,
+                    ))?;
+                ink::MessageResult::<R>::decode(&mut &script(selector, input)[..])
+                    .map_err(EnvError::Decode)
+            }
+
+            pub fn register(code_hash: Hash, script: impl Fn([u8; 4], &[u8]) -> Vec<u8> + 'static) {
+                SCRIPTS.with(|scripts| scripts.borrow_mut().push((code_hash, Rc::new(script))));
+            }
+
+            pub fn own_code_hash() -> Hash {
+                CODE_HASH.with(|code_hash| *code_hash.borrow())
+            }
+
+            pub fn set_own_code_hash(code_hash: Hash) {
+                CODE_HASH.with(|current| *current.borrow_mut() = code_hash);
+            }
+        }
+
+        fn accounts() -> DefaultAccounts<Environment> {
+            default_accounts::<Environment>()
+        }
+
+        fn runtime_call_tx(call: Vec<u8>) -> Transaction {
+            Transaction {
+                address: accounts().django,
+                selector: [0; 4],
+                input: call,
+                transferred_value: 0,
+                ref_time_limit: 0,
+                allow_reentry: false,
+                proof_size_limit: 0,
+                storage_deposit_limit: None,
+                call_type: CallType::RuntimeCall,
+            }
+        }
+
+        fn new_multisig(threshold: u8, owners: Vec<AccountId>) -> MultiSig {
+            // The default callee is alice, so the contract gets its own account
+            set_callee::<Environment>(AccountId::from([0xff; 32]));
+            set_caller::<Environment>(accounts().alice);
+            MultiSig::new(threshold, owners)
+                .ok()
+                .expect("Constructor params are valid")
+        }
+
+        fn emitted_events<E: ink::env::Event + scale::Decode>() -> Vec<E> {
+            recorded_events()
+                .filter(|event| {
+                    event.topics.first().map(Vec::as_slice)
+                        == E::SIGNATURE_TOPIC.as_ref().map(|topic| &topic[..])
+                })
+                .map(|event| E::decode(&mut &event.data[..]).expect("The event is decodable"))
+                .collect()
+        }
+
+        /// Registers a script that reports the layout version and runs the given closure
+        fn register_script(code_hash: Hash, layout_version: u32, run: impl Fn() + 'static) {
+            mocked_scripts::register(code_hash, move |selector, _input| {
+                if selector == SCRIPT_STORAGE_LAYOUT_VERSION_SELECTOR {
+                    return Ok::<u32, LangError>(layout_version).encode();
+                }
+                run();
+                Ok::<(), LangError>(()).encode()
+            });
+        }
+
+        fn stored_multisig() -> MultiSig {
+            ink::env::get_contract_storage(&<MultiSig as StorageKey>::KEY)
+                .ok()
+                .flatten()
+                .expect("The root has been written")
+        }
+
+        fn last_execution_result() -> TxResult {
+            emitted_events::<TransactionExecuted>()
+                .pop()
+                .expect("A transaction has been executed")
+                .result
+        }
+
+        #[ink::test]
+        fn runtime_call_is_dispatched_when_threshold_is_met() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+
+            // Runtime calls are admin transactions, so they need the admin threshold
+            let call = vec![4, 0, 1, 2, 3];
+            assert!(multisig.propose_tx(runtime_call_tx(call.clone())).is_ok());
+            assert_eq!(multisig.get_tx_threshold(0), Some(2));
+            assert!(mocked_runtime::dispatched().is_empty());
+
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.approve_tx(0).is_ok());
+
+            assert_eq!(mocked_runtime::dispatched(), vec![call]);
+            assert!(
+                matches!(last_execution_result(), TxResult::Success(output) if output.is_empty())
+            );
+            assert!(multisig.get_tx(0).is_none());
+        }
+
+        #[ink::test]
+        fn runtime_call_failure_is_reported_in_the_result() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+            mocked_runtime::set_failing(true);
+
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+
+            assert_eq!(mocked_runtime::dispatched().len(), 1);
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Failed(MultisigError::EnvExecutionFailed(_))
+            ));
+            assert!(multisig.get_tx(0).is_none());
+        }
+
+        #[ink::test]
+        fn staking_helpers_dispatch_runtime_calls_and_record_them() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+
+            // Staking helpers are self calls, and the pallet indices of the runtime must be configured
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(matches!(
+                multisig.bond(1000, RewardDestination::Staked),
+                Err(MultisigError::StakingNotConfigured)
+            ));
+            assert!(multisig
+                .set_staking_pallets(Some(staking_pallets()))
+                .is_ok());
+            assert!(multisig.bond(1000, RewardDestination::Staked).is_ok());
+            assert!(multisig.nominate(vec![accounts.django]).is_ok());
+            assert!(multisig.join_pool(1000, 3).is_ok());
+
+            // Staking.bond(Compact(1000), Staked)
+            let bond = vec![10, 0, 0xa1, 0x0f, 0];
+            // Staking.nominate([MultiAddress::Id(django)])
+            let mut nominate = vec![10, 5, 4, 0];
+            nominate.extend_from_slice(accounts.django.as_ref());
+            // NominationPools.join(Compact(1000), 3)
+            let join_pool = vec![40, 0, 0xa1, 0x0f, 3, 0, 0, 0];
+            assert_eq!(
+                mocked_runtime::dispatched(),
+                vec![bond, nominate, join_pool]
+            );
+
+            assert_eq!(multisig.get_staking_records_count(), 3);
+            let operations: Vec<StakingOperation> = multisig
+                .get_staking_records(1, 10)
+                .into_iter()
+                .map(|record| record.operation)
+                .collect();
+            assert!(
+                operations
+                    == vec![
+                        StakingOperation::Nominate {
+                            targets: vec![accounts.django]
+                        },
+                        StakingOperation::JoinPool {
+                            amount: 1000,
+                            pool_id: 3
+                        },
+                    ]
+            );
+            assert_eq!(recorded_events().count(), 4);
+        }
+
+        #[ink::test]
+        fn staking_helpers_fail_when_not_self_call_or_dispatch_fails() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+
+            // Owners cannot call the staking helpers directly
+            assert!(matches!(
+                multisig.unbond(1000),
+                Err(MultisigError::Unauthorized)
+            ));
+            assert!(mocked_runtime::dispatched().is_empty());
+
+            // A failed dispatch is not recorded
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig
+                .set_staking_pallets(Some(staking_pallets()))
+                .is_ok());
+            mocked_runtime::set_failing(true);
+            assert!(matches!(
+                multisig.claim_pool_payout(),
+                Err(MultisigError::EnvExecutionFailed(_))
+            ));
+            assert_eq!(mocked_runtime::dispatched(), vec![vec![40, 2]]);
+            assert_eq!(multisig.get_staking_records_count(), 0);
+            assert!(multisig.get_staking_record(0).is_none());
+        }
+
+        #[ink::test]
+        fn votes_of_removed_owners_are_cleaned_up_and_slots_are_reused() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(2, vec![accounts.alice, accounts.bob, accounts.charlie]);
+
+            // Alice proposes a transaction and Bob rejects it
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.reject_tx(0).is_ok());
+            let bob_slot = multisig.owner_slots.get(accounts.bob);
+
+            // Bob is replaced by Eve, who can't take Bob's slot while his vote is active
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.remove_owner(accounts.bob).is_ok());
+            assert!(multisig.add_owner(accounts.eve).is_ok());
+            assert_ne!(multisig.owner_slots.get(accounts.eve), bob_slot);
+            assert_eq!(
+                multisig.get_tx_voters(0),
+                Some(vec![accounts.alice, accounts.bob])
+            );
+
+            // Charlie's rejection cancels the transaction, removing Bob's vote as well
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.reject_tx(0).is_ok());
+            assert!(multisig.get_tx(0).is_none());
+            assert!(multisig.get_tx_voters(0).is_none());
+            assert_eq!(multisig.get_tx_approval_for_account(0, accounts.bob), None);
+            assert_eq!(
+                multisig.get_tx_approval_for_account(0, accounts.alice),
+                None
+            );
+
+            // Bob gets his slot back when he is added again
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.add_owner(accounts.bob).is_ok());
+            assert_eq!(multisig.owner_slots.get(accounts.bob), bob_slot);
+        }
+
+        #[ink::test]
+        fn votes_are_packed_in_a_single_storage_item() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(3, vec![accounts.alice, accounts.bob, accounts.charlie]);
+            let used_cells = || {
+                count_used_storage_cells::<Environment>(&callee::<Environment>())
+                    .expect("The contract account exists")
+            };
+            let initial_cells = used_cells();
+
+            // A proposal stores the transaction, its active position (both ways) and its votes record
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            assert_eq!(used_cells(), initial_cells + 4);
+
+            // Votes update the same record, whatever the number of voters
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.approve_tx(0).is_ok());
+            assert_eq!(used_cells(), initial_cells + 4);
+            let votes = multisig.votes.get(0).expect("The transaction is active");
+            assert_eq!(votes.encoded_size(), 46);
+            assert_eq!(votes.approvals_count, 2);
+            assert_eq!(
+                multisig.get_tx_approval_for_account(0, accounts.bob),
+                Some(true)
+            );
+            assert_eq!(
+                multisig.get_tx_approval_for_account(0, accounts.charlie),
+                None
+            );
+
+            // The rejection cancels the transaction and every item is removed
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.reject_tx(0).is_ok());
+            assert!(multisig.get_tx(0).is_none());
+            assert_eq!(used_cells(), initial_cells);
+        }
+
+        #[ink::test]
+        fn active_txs_are_swap_removed_and_paginated() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+
+            for _ in 0..4 {
+                assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            }
+            assert_eq!(multisig.get_active_txid_list(), vec![0, 1, 2, 3]);
+
+            // Bob's rejection cancels the transaction 1, and the last one takes its position
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.reject_tx(1).is_ok());
+            assert_eq!(multisig.get_active_txid_list(), vec![0, 3, 2]);
+            assert_eq!(multisig.get_active_txs_count(), 3);
+
+            // Removing the last transaction doesn't move any other
+            assert!(multisig.reject_tx(2).is_ok());
+            assert_eq!(multisig.get_active_txid_list(), vec![0, 3]);
+
+            // Pages are bounded by the number of active transactions
+            assert_eq!(multisig.get_active_txids(1, 10), vec![3]);
+            assert_eq!(multisig.get_active_txids(2, 10), Vec::<TxId>::new());
+            assert_eq!(
+                multisig.get_active_txids(u32::MAX, u32::MAX),
+                Vec::<TxId>::new()
+            );
         }
 
-        // Check that threshold is not zero
-        if threshold == 0 {
-            return Err(MultisigError::ThresholdCantBeZero);
+        #[ink::test]
+        fn tx_status_aggregates_votes_and_threshold() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(2, vec![accounts.alice, accounts.bob, accounts.charlie]);
+
+            // Bob proposes a transaction and Charlie rejects it
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.reject_tx(0).is_ok());
+
+            let status = multisig
+                .get_tx_status(0)
+                .expect("The transaction is active");
+            assert_eq!(status.tx.input, vec![0, 0]);
+            assert!(status.tx.call_type == CallType::RuntimeCall);
+            assert_eq!(status.proposer, accounts.bob);
+            assert_eq!(status.approvals, 1);
+            assert_eq!(status.rejections, 1);
+            assert_eq!(
+                status.votes,
+                vec![(accounts.bob, true), (accounts.charlie, false)]
+            );
+            assert_eq!(status.threshold, 2);
+            assert!(!status.executable);
+
+            assert!(multisig.get_tx_status(1).is_none());
         }
 
-        Ok(())
+        #[ink::test]
+        fn pending_txs_are_filtered_and_paginated() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(3, vec![accounts.alice, accounts.bob, accounts.charlie]);
+
+            // Alice proposes a runtime call, and Bob a regular call to Django that Charlie approves
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            set_caller::<Environment>(accounts.bob);
+            let call = || Transaction {
+                call_type: CallType::Call,
+                ..runtime_call_tx(vec![])
+            };
+            assert!(multisig.propose_tx(call()).is_ok());
+            assert!(multisig.propose_tx(call()).is_ok());
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.approve_tx(1).is_ok());
+
+            let pending_ids = |offset, limit, filter| -> Vec<TxId> {
+                multisig
+                    .get_pending_txs(offset, limit, filter)
+                    .into_iter()
+                    .map(|(tx_id, _)| tx_id)
+                    .collect()
+            };
+
+            assert_eq!(
+                pending_ids(0, 10, PendingTxsFilter::default()),
+                vec![0, 1, 2]
+            );
+            assert_eq!(pending_ids(1, 1, PendingTxsFilter::default()), vec![1]);
+            let by_bob = PendingTxsFilter {
+                proposer: Some(accounts.bob),
+                ..Default::default()
+            };
+            assert_eq!(pending_ids(0, 10, by_bob.clone()), vec![1, 2]);
+            assert_eq!(pending_ids(2, 10, by_bob.clone()), vec![2]);
+
+            // The pages are taken from the active transactions, so they can have fewer matches
+            assert_eq!(pending_ids(0, 1, by_bob), Vec::<TxId>::new());
+            let to_django = PendingTxsFilter {
+                target: Some(accounts.django),
+                ..Default::default()
+            };
+            assert_eq!(pending_ids(0, 10, to_django), vec![1, 2]);
+            let awaiting_charlie = PendingTxsFilter {
+                awaiting_vote_of: Some(accounts.charlie),
+                ..Default::default()
+            };
+            assert_eq!(pending_ids(0, 10, awaiting_charlie), vec![0, 2]);
+            let awaiting_django = PendingTxsFilter {
+                awaiting_vote_of: Some(accounts.django),
+                ..Default::default()
+            };
+            assert_eq!(pending_ids(0, 10, awaiting_django), Vec::<TxId>::new());
+
+            let (_, status) = multisig
+                .get_pending_txs(0, 1, PendingTxsFilter::default())
+                .pop()
+                .expect("There are pending transactions");
+            assert_eq!(status.proposer, accounts.alice);
+            assert_eq!(status.approvals, 1);
+        }
+
+        #[ink::test]
+        fn txs_awaiting_vote_are_listed_per_owner() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(2, vec![accounts.alice, accounts.bob, accounts.charlie]);
+
+            // Alice proposes two transactions and Bob rejects the second one
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 1])).is_ok());
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.reject_tx(1).is_ok());
+
+            let awaiting_ids = |owner| -> Vec<TxId> {
+                multisig
+                    .get_txs_awaiting_vote(owner)
+                    .into_iter()
+                    .map(|summary| summary.tx_id)
+                    .collect()
+            };
+            assert_eq!(awaiting_ids(accounts.alice), Vec::<TxId>::new());
+            assert_eq!(awaiting_ids(accounts.bob), vec![0]);
+            assert_eq!(awaiting_ids(accounts.charlie), vec![0, 1]);
+            assert_eq!(awaiting_ids(accounts.django), Vec::<TxId>::new());
+
+            let summary = multisig.get_txs_awaiting_vote(accounts.charlie).remove(1);
+            assert_eq!(summary.proposer, accounts.alice);
+            assert_eq!(summary.approvals, 1);
+            assert_eq!(summary.rejections, 1);
+            assert_eq!(summary.threshold, 2);
+        }
+
+        #[ink::test]
+        fn owners_are_swapped_and_configured_atomically() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            set_caller::<Environment>(callee::<Environment>());
+
+            // A 2 of 2 signer can be rotated in a single step
+            assert!(multisig.swap_owner(accounts.bob, accounts.charlie).is_ok());
+            assert_eq!(
+                multisig.get_owners(),
+                vec![accounts.alice, accounts.charlie]
+            );
+            assert!(!multisig.is_owner(accounts.bob));
+            assert_eq!(multisig.get_threshold(), 2);
+
+            // Only the final state is checked
+            assert!(multisig
+                .set_config(
+                    vec![accounts.django, accounts.eve],
+                    vec![accounts.alice, accounts.charlie],
+                    2
+                )
+                .is_ok());
+            assert_eq!(multisig.get_owners(), vec![accounts.django, accounts.eve]);
+
+            assert!(matches!(
+                multisig.set_config(vec![], vec![accounts.eve], 2),
+                Err(MultisigError::ThresholdGreaterThanOwners)
+            ));
+            assert!(matches!(
+                multisig.set_config(vec![], vec![accounts.django, accounts.eve], 1),
+                Err(MultisigError::OwnersCantBeEmpty)
+            ));
+            assert!(matches!(
+                multisig.set_config(vec![accounts.bob], vec![accounts.alice], 1),
+                Err(MultisigError::NotOwner)
+            ));
+            assert!(matches!(
+                multisig.swap_owner(accounts.django, accounts.eve),
+                Err(MultisigError::OwnerAlreadyExists)
+            ));
+            assert!(matches!(
+                multisig.set_config(vec![accounts.bob], vec![], 0),
+                Err(MultisigError::ThresholdCantBeZero)
+            ));
+            assert_eq!(multisig.get_owners(), vec![accounts.django, accounts.eve]);
+        }
+
+        fn staking_pallets() -> StakingPallets {
+            StakingPallets {
+                staking: 10,
+                nomination_pools: 40,
+            }
+        }
+
+        fn recovery_config(guardians: Vec<AccountId>, quorum: u8) -> Option<RecoveryConfig> {
+            Some(RecoveryConfig {
+                guardians,
+                quorum,
+                delay: MIN_RECOVERY_DELAY,
+            })
+        }
+
+        #[ink::test]
+        fn guardians_recover_the_wallet_after_the_delay() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+
+            // The guardians are configured by the owners
+            assert!(matches!(
+                multisig.set_recovery_config(recovery_config(vec![accounts.charlie], 1)),
+                Err(MultisigError::Unauthorized)
+            ));
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(matches!(
+                multisig.set_recovery_config(recovery_config(vec![accounts.charlie], 2)),
+                Err(MultisigError::InvalidRecoveryConfig)
+            ));
+            assert!(matches!(
+                multisig.set_recovery_config(Some(RecoveryConfig {
+                    delay: MIN_RECOVERY_DELAY - 1,
+                    ..recovery_config(vec![accounts.charlie], 1).expect("The config is set")
+                })),
+                Err(MultisigError::InvalidRecoveryConfig)
+            ));
+            assert!(multisig
+                .set_recovery_config(recovery_config(vec![accounts.charlie, accounts.django], 2))
+                .is_ok());
+
+            // Charlie proposes Eve as the only owner and Django approves it
+            set_caller::<Environment>(accounts.alice);
+            assert!(matches!(
+                multisig.propose_recovery(vec![accounts.eve], 1),
+                Err(MultisigError::NotGuardian)
+            ));
+            set_caller::<Environment>(accounts.charlie);
+            assert!(matches!(
+                multisig.propose_recovery(vec![accounts.eve], 2),
+                Err(MultisigError::ThresholdGreaterThanOwners)
+            ));
+            assert!(multisig.propose_recovery(vec![accounts.eve], 1).is_ok());
+            assert!(matches!(
+                multisig.approve_recovery(),
+                Err(MultisigError::AlreadyVoted)
+            ));
+            assert!(matches!(
+                multisig.execute_recovery(),
+                Err(MultisigError::RecoveryNotExecutable)
+            ));
+            set_block_timestamp::<Environment>(500);
+            set_caller::<Environment>(accounts.django);
+            assert!(multisig.approve_recovery().is_ok());
+            assert!(matches!(
+                multisig.reject_recovery(),
+                Err(MultisigError::AlreadyVoted)
+            ));
+            let recovery = multisig
+                .get_pending_recovery()
+                .expect("The recovery is pending");
+            assert_eq!(recovery.executable_at, Some(500 + MIN_RECOVERY_DELAY));
+
+            // The recovery can only be executed once the delay has elapsed
+            set_block_timestamp::<Environment>(500 + MIN_RECOVERY_DELAY - 1);
+            assert!(matches!(
+                multisig.execute_recovery(),
+                Err(MultisigError::RecoveryNotExecutable)
+            ));
+            // The former owners set up other ways to control the wallet in the meantime
+            multisig.modules_list.push(accounts.frank);
+            multisig.guard = Some(accounts.frank);
+
+            set_block_timestamp::<Environment>(500 + MIN_RECOVERY_DELAY);
+            assert!(multisig.execute_recovery().is_ok());
+
+            assert_eq!(multisig.get_owners(), vec![accounts.eve]);
+            assert_eq!(multisig.get_threshold(), 1);
+            assert_eq!(multisig.get_active_txid_list(), Vec::<TxId>::new());
+            assert!(multisig.get_pending_recovery().is_none());
+
+            // which are revoked with them
+            assert_eq!(multisig.get_modules(), Vec::<AccountId>::new());
+            assert_eq!(multisig.get_guard(), None);
+            assert_eq!(emitted_events::<ModuleDisabled>().len(), 1);
+        }
+
+        #[ink::test]
+        fn bogus_recovery_proposals_are_rejected_or_expire() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig
+                .set_recovery_config(recovery_config(
+                    vec![accounts.charlie, accounts.django, accounts.eve],
+                    2
+                ))
+                .is_ok());
+
+            // Charlie proposes to be the only owner, the other guardians reject it so the quorum cannot be reached
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.propose_recovery(vec![accounts.charlie], 1).is_ok());
+            set_caller::<Environment>(accounts.django);
+            assert!(multisig.reject_recovery().is_ok());
+            assert!(multisig.get_pending_recovery().is_some());
+            set_caller::<Environment>(accounts.eve);
+            assert!(multisig.reject_recovery().is_ok());
+            assert!(multisig.get_pending_recovery().is_none());
+            assert_eq!(emitted_events::<RecoveryRejected>().len(), 2);
+            assert_eq!(emitted_events::<RecoveryCancelled>().len(), 1);
+
+            // A proposal that does not reach the quorum in time expires and can be replaced
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.propose_recovery(vec![accounts.charlie], 1).is_ok());
+            assert!(matches!(
+                multisig.propose_recovery(vec![accounts.eve], 1),
+                Err(MultisigError::RecoveryAlreadyPending)
+            ));
+            set_block_timestamp::<Environment>(RECOVERY_PROPOSAL_PERIOD);
+            assert!(multisig.get_pending_recovery().is_none());
+            set_caller::<Environment>(accounts.django);
+            assert!(matches!(
+                multisig.approve_recovery(),
+                Err(MultisigError::RecoveryExpired)
+            ));
+            assert!(multisig.propose_recovery(vec![accounts.eve], 1).is_ok());
+            assert_eq!(emitted_events::<RecoveryCancelled>().len(), 2);
+
+            // Once it reaches the quorum the guardians cannot reject it anymore, it never expires
+            // and only the owners can cancel it
+            set_caller::<Environment>(accounts.eve);
+            assert!(multisig.approve_recovery().is_ok());
+            set_caller::<Environment>(accounts.charlie);
+            assert!(matches!(
+                multisig.reject_recovery(),
+                Err(MultisigError::RecoveryQuorumAlreadyReached)
+            ));
+            set_block_timestamp::<Environment>(3 * RECOVERY_PROPOSAL_PERIOD);
+            assert!(matches!(
+                multisig.propose_recovery(vec![accounts.charlie], 1),
+                Err(MultisigError::RecoveryAlreadyPending)
+            ));
+            assert!(multisig.execute_recovery().is_ok());
+            assert_eq!(multisig.get_owners(), vec![accounts.eve]);
+        }
+
+        #[ink::test]
+        fn owners_cancel_the_pending_recovery() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig
+                .set_recovery_config(recovery_config(vec![accounts.charlie], 1))
+                .is_ok());
+
+            set_caller::<Environment>(accounts.alice);
+            assert!(matches!(
+                multisig.cancel_recovery(),
+                Err(MultisigError::NoPendingRecovery)
+            ));
+
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.propose_recovery(vec![accounts.eve], 1).is_ok());
+            assert!(matches!(
+                multisig.propose_recovery(vec![accounts.bob], 1),
+                Err(MultisigError::RecoveryAlreadyPending)
+            ));
+
+            // Guardians cannot cancel it, but any single owner can without reaching the threshold
+            assert!(matches!(
+                multisig.cancel_recovery(),
+                Err(MultisigError::NotOwner)
+            ));
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.cancel_recovery().is_ok());
+            assert!(multisig.get_pending_recovery().is_none());
+
+            set_block_timestamp::<Environment>(MIN_RECOVERY_DELAY);
+            assert!(matches!(
+                multisig.execute_recovery(),
+                Err(MultisigError::NoPendingRecovery)
+            ));
+            assert_eq!(multisig.get_owners(), vec![accounts.alice, accounts.bob]);
+        }
+
+        #[ink::test]
+        fn beneficiaries_take_control_after_inactivity() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice, accounts.bob]);
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(matches!(
+                multisig.set_inheritance_config(Some(InheritanceConfig {
+                    beneficiaries: vec![accounts.charlie],
+                    inactivity_period: 10,
+                    action: InheritanceAction::TakeControl(2),
+                })),
+                Err(MultisigError::InvalidInheritanceConfig)
+            ));
+            assert!(multisig
+                .set_inheritance_config(Some(InheritanceConfig {
+                    beneficiaries: vec![accounts.charlie, accounts.django],
+                    inactivity_period: 10,
+                    action: InheritanceAction::TakeControl(2),
+                }))
+                .is_ok());
+            assert_eq!(multisig.get_inheritance_claimable_at(), Some(10));
+
+            // The former owners set up other ways to control the wallet
+            assert!(multisig.enable_module(accounts.frank).is_ok());
+            assert!(multisig
+                .set_recovery_config(recovery_config(vec![accounts.eve, accounts.frank], 2))
+                .is_ok());
+            multisig.guard = Some(accounts.frank);
+            set_caller::<Environment>(accounts.eve);
+            assert!(multisig.propose_recovery(vec![accounts.eve], 1).is_ok());
+
+            // Bob's heartbeat postpones the claim
+            for _ in 0..5 {
+                ink::env::test::advance_block::<Environment>();
+            }
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.heartbeat().is_ok());
+            assert_eq!(multisig.get_owner_last_activity(accounts.bob), Some(5));
+            assert_eq!(multisig.get_owner_last_activity(accounts.alice), None);
+            assert_eq!(multisig.get_inheritance_claimable_at(), Some(15));
+
+            for _ in 0..9 {
+                ink::env::test::advance_block::<Environment>();
+            }
+            set_caller::<Environment>(accounts.charlie);
+            assert!(matches!(
+                multisig.claim_inheritance(),
+                Err(MultisigError::OwnersStillActive)
+            ));
+            set_caller::<Environment>(accounts.eve);
+            assert!(matches!(
+                multisig.claim_inheritance(),
+                Err(MultisigError::NotBeneficiary)
+            ));
+
+            // The beneficiaries replace the owners once the period has elapsed
+            ink::env::test::advance_block::<Environment>();
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.claim_inheritance().is_ok());
+            assert_eq!(
+                multisig.get_owners(),
+                vec![accounts.charlie, accounts.django]
+            );
+            assert_eq!(multisig.get_threshold(), 2);
+            assert!(multisig.get_inheritance_config().is_none());
+            assert_eq!(multisig.get_owner_last_activity(accounts.bob), None);
+
+            // The control paths of the former owners are revoked
+            assert!(multisig.get_pending_recovery().is_none());
+            assert!(multisig.get_modules().is_empty());
+            assert!(!multisig.is_module(accounts.frank));
+            assert_eq!(multisig.get_guard(), None);
+        }
+
+        #[ink::test]
+        fn pause_blocks_proposals_and_executions_until_it_expires() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.set_pause_guardian(Some(accounts.charlie)).is_ok());
+            assert!(multisig.set_pause_duration(3).is_ok());
+
+            // Alice proposes a transaction and the pause guardian pauses the wallet
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            set_caller::<Environment>(accounts.django);
+            assert!(matches!(multisig.pause(), Err(MultisigError::Unauthorized)));
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.pause().is_ok());
+            assert!(multisig.is_paused());
+            assert_eq!(multisig.get_paused_until(), Some(3));
+
+            // Only the transactions that unpause the wallet can be proposed
+            set_caller::<Environment>(accounts.alice);
+            assert!(matches!(
+                multisig.propose_tx(runtime_call_tx(vec![0, 1])),
+                Err(MultisigError::Paused)
+            ));
+            assert!(multisig
+                .propose_tx(Transaction {
+                    address: callee::<Environment>(),
+                    selector: ink::selector_bytes!("unpause"),
+                    call_type: CallType::Call,
+                    ..runtime_call_tx(vec![])
+                })
+                .is_ok());
+
+            // The approved transaction is not executed while paused
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.approve_tx(0).is_ok());
+            assert!(multisig.get_tx(0).is_some());
+            assert!(matches!(
+                multisig.try_execute_tx(0),
+                Err(MultisigError::Paused)
+            ));
+            assert!(mocked_runtime::dispatched().is_empty());
+
+            // The pause expires automatically
+            for _ in 0..3 {
+                ink::env::test::advance_block::<Environment>();
+            }
+            assert!(!multisig.is_paused());
+            assert!(multisig.try_execute_tx(0).is_ok());
+            assert_eq!(mocked_runtime::dispatched(), vec![vec![0, 0]]);
+
+            // The owners can unpause the wallet before it expires
+            assert!(multisig.pause().is_ok());
+            assert!(matches!(
+                multisig.unpause(),
+                Err(MultisigError::Unauthorized)
+            ));
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.unpause().is_ok());
+            assert!(!multisig.is_paused());
+        }
+
+        #[ink::test]
+        fn delegate_votes_on_behalf_of_the_owner_until_it_expires() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(3, vec![accounts.alice, accounts.bob, accounts.charlie]);
+
+            // Bob delegates his votes to Eve until block 2
+            set_caller::<Environment>(accounts.bob);
+            assert!(matches!(
+                multisig.delegate_votes(accounts.charlie, 2),
+                Err(MultisigError::InvalidDelegation)
+            ));
+            assert!(matches!(
+                multisig.delegate_votes(accounts.eve, 0),
+                Err(MultisigError::InvalidDelegation)
+            ));
+            assert!(multisig.delegate_votes(accounts.eve, 2).is_ok());
+            assert_eq!(multisig.get_delegator(accounts.eve), Some(accounts.bob));
+
+            // Eve cannot vote on behalf of another owner at the same time
+            set_caller::<Environment>(accounts.charlie);
+            assert!(matches!(
+                multisig.delegate_votes(accounts.eve, 2),
+                Err(MultisigError::DelegateAlreadyAssigned)
+            ));
+
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 1])).is_ok());
+
+            // Eve's approval counts as Bob's vote and both identities are recorded
+            set_caller::<Environment>(accounts.eve);
+            assert!(multisig.approve_tx(0).is_ok());
+            assert_eq!(
+                multisig.get_tx_approval_for_account(0, accounts.bob),
+                Some(true)
+            );
+            let status = multisig
+                .get_tx_status(0)
+                .expect("The transaction is active");
+            assert_eq!(status.approvals, 2);
+            assert_eq!(status.delegated_votes, vec![(accounts.bob, accounts.eve)]);
+
+            // Bob cannot vote again
+            set_caller::<Environment>(accounts.bob);
+            assert!(matches!(
+                multisig.approve_tx(0),
+                Err(MultisigError::AlreadyVoted)
+            ));
+
+            // The delegation expires at block 2
+            ink::env::test::advance_block::<Environment>();
+            ink::env::test::advance_block::<Environment>();
+            set_caller::<Environment>(accounts.eve);
+            assert!(matches!(
+                multisig.approve_tx(1),
+                Err(MultisigError::NotOwner)
+            ));
+            assert_eq!(multisig.get_delegator(accounts.eve), None);
+
+            // Expired delegations can still be revoked, then there is nothing to revoke
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.revoke_delegation().is_ok());
+            assert!(multisig.get_delegation(accounts.bob).is_none());
+            assert!(matches!(
+                multisig.revoke_delegation(),
+                Err(MultisigError::NoDelegation)
+            ));
+
+            // The delegate of a cancelled transaction is removed with its votes
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.reject_tx(0).is_ok());
+            assert!(multisig.get_tx(0).is_none());
+            assert!(multisig.vote_delegates.get((0, 1)).is_none());
+        }
+
+        #[ink::test]
+        fn nested_multisig_calls_are_self_calls() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+            let outer = AccountId::from([0xee; 32]);
+
+            // Only the multisig itself can vote in the outer multisig
+            assert!(matches!(
+                multisig.propose_in(outer, runtime_call_tx(vec![0, 0])),
+                Err(MultisigError::Unauthorized)
+            ));
+            assert!(matches!(
+                multisig.approve_in(outer, 0),
+                Err(MultisigError::Unauthorized)
+            ));
+            assert!(matches!(
+                multisig.reject_in(outer, 0),
+                Err(MultisigError::Unauthorized)
+            ));
+        }
+
+        #[ink::test]
+        fn rejection_threshold_cancels_as_soon_as_it_is_reached() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(
+                3,
+                vec![
+                    accounts.alice,
+                    accounts.bob,
+                    accounts.charlie,
+                    accounts.django,
+                    accounts.eve,
+                ],
+            );
+
+            // The rejection threshold is validated like the threshold
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(matches!(
+                multisig.change_rejection_threshold(Some(0)),
+                Err(MultisigError::ThresholdCantBeZero)
+            ));
+            assert!(matches!(
+                multisig.change_rejection_threshold(Some(6)),
+                Err(MultisigError::ThresholdGreaterThanOwners)
+            ));
+
+            // Without it a 3-of-5 wallet needs 3 rejections to cancel a transaction
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            for owner in [accounts.bob, accounts.charlie] {
+                set_caller::<Environment>(owner);
+                assert!(multisig.reject_tx(0).is_ok());
+            }
+            assert!(multisig.get_tx(0).is_some());
+
+            // With a rejection threshold of 2 the next proposal is cancelled by the second rejection
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.change_rejection_threshold(Some(2)).is_ok());
+            assert_eq!(multisig.get_rejection_threshold(), Some(2));
+
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 1])).is_ok());
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.reject_tx(1).is_ok());
+            assert!(multisig.get_tx(1).is_some());
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.reject_tx(1).is_ok());
+            assert!(multisig.get_tx(1).is_none());
+        }
+
+        #[ink::test]
+        fn threshold_ratio_is_recomputed_when_owners_change() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(1, vec![accounts.alice, accounts.bob, accounts.charlie]);
+            let ratio = |rounding, min_threshold| ThresholdRatio {
+                numerator: 2,
+                denominator: 3,
+                rounding,
+                min_threshold,
+            };
+
+            // 2/3 of 4 owners with each rounding
+            assert_eq!(ratio(ThresholdRounding::Up, 1).threshold(4), 3);
+            assert_eq!(ratio(ThresholdRounding::Down, 1).threshold(4), 2);
+            assert_eq!(ratio(ThresholdRounding::Nearest, 1).threshold(4), 3);
+            assert_eq!(ratio(ThresholdRounding::Nearest, 1).threshold(2), 1);
+
+            // The ratio cannot be zero or greater than 1, and its minimum must fit the owners
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(matches!(
+                multisig.set_threshold_ratio(Some(ThresholdRatio {
+                    numerator: 4,
+                    ..ratio(ThresholdRounding::Up, 1)
+                })),
+                Err(MultisigError::InvalidThresholdRatio)
+            ));
+            assert!(matches!(
+                multisig.set_threshold_ratio(Some(ratio(ThresholdRounding::Up, 0))),
+                Err(MultisigError::InvalidThresholdRatio)
+            ));
+            assert!(matches!(
+                multisig.set_threshold_ratio(Some(ratio(ThresholdRounding::Up, 4))),
+                Err(MultisigError::ThresholdGreaterThanOwners)
+            ));
+
+            // A 2/3 majority of 3 owners
+            assert!(multisig
+                .set_threshold_ratio(Some(ratio(ThresholdRounding::Up, 2)))
+                .is_ok());
+            assert_eq!(multisig.get_threshold(), 2);
+
+            // Adding and removing owners keeps the majority without a threshold change
+            assert!(multisig.add_owner(accounts.django).is_ok());
+            assert_eq!(multisig.get_threshold(), 3);
+            assert!(multisig.remove_owner(accounts.alice).is_ok());
+            assert_eq!(multisig.get_threshold(), 2);
+            assert!(multisig.remove_owner(accounts.bob).is_ok());
+            assert_eq!(multisig.get_threshold(), 2);
+
+            // Only the minimum threshold blocks a removal
+            assert!(matches!(
+                multisig.remove_owner(accounts.charlie),
+                Err(MultisigError::ThresholdGreaterThanOwners)
+            ));
+
+            // A fixed threshold replaces the ratio
+            assert!(multisig.change_threshold(1).is_ok());
+            assert!(multisig.get_threshold_ratio().is_none());
+            assert!(multisig.add_owner(accounts.alice).is_ok());
+            assert_eq!(multisig.get_threshold(), 1);
+        }
+
+        #[ink::test]
+        fn revoking_an_expired_delegation_keeps_the_delegate_of_another_owner() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(
+                3,
+                vec![
+                    accounts.alice,
+                    accounts.bob,
+                    accounts.charlie,
+                    accounts.django,
+                ],
+            );
+
+            // Alice and Charlie delegate until block 1
+            assert!(multisig.delegate_votes(accounts.eve, 1).is_ok());
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.delegate_votes(accounts.frank, 1).is_ok());
+
+            // Once they expire Bob and Django delegate to the same accounts
+            ink::env::test::advance_block::<Environment>();
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.delegate_votes(accounts.eve, 10).is_ok());
+            set_caller::<Environment>(accounts.django);
+            assert!(multisig.delegate_votes(accounts.frank, 10).is_ok());
+
+            // Alice revokes her expired delegation and Bob's one is kept
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.revoke_delegation().is_ok());
+            assert_eq!(multisig.get_delegator(accounts.eve), Some(accounts.bob));
+
+            // Charlie is removed and Django's delegation is kept
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.remove_owner(accounts.charlie).is_ok());
+            assert!(multisig.get_delegation(accounts.charlie).is_none());
+            assert_eq!(
+                multisig.get_delegator(accounts.frank),
+                Some(accounts.django)
+            );
+        }
+
+        #[ink::test]
+        fn pause_cannot_be_extended_and_keeps_owner_management_available() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.set_pause_guardian(Some(accounts.charlie)).is_ok());
+            assert!(multisig
+                .set_recovery_config(recovery_config(vec![accounts.django], 1))
+                .is_ok());
+            assert!(multisig
+                .set_inheritance_config(Some(InheritanceConfig {
+                    beneficiaries: vec![accounts.eve],
+                    inactivity_period: 1,
+                    action: InheritanceAction::TakeControl(1),
+                }))
+                .is_ok());
+
+            // A compromised pause guardian cannot extend the pause
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.pause().is_ok());
+            let paused_until = multisig.get_paused_until();
+            assert!(matches!(multisig.pause(), Err(MultisigError::Paused)));
+            assert_eq!(multisig.get_paused_until(), paused_until);
+
+            // The owners can still propose to evict it
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig
+                .propose_tx(Transaction {
+                    address: callee::<Environment>(),
+                    selector: ink::selector_bytes!("set_pause_guardian"),
+                    call_type: CallType::Call,
+                    ..runtime_call_tx(vec![0])
+                })
+                .is_ok());
+            assert!(multisig
+                .propose_tx(Transaction {
+                    address: callee::<Environment>(),
+                    selector: ink::selector_bytes!("remove_owner"),
+                    call_type: CallType::Call,
+                    ..runtime_call_tx(vec![])
+                })
+                .is_ok());
+
+            // The inheritance cannot be claimed while paused
+            ink::env::test::advance_block::<Environment>();
+            set_caller::<Environment>(accounts.eve);
+            assert!(matches!(
+                multisig.claim_inheritance(),
+                Err(MultisigError::Paused)
+            ));
+
+            // The recovery can be executed while paused
+            set_caller::<Environment>(accounts.django);
+            assert!(multisig.propose_recovery(vec![accounts.frank], 1).is_ok());
+            let executable_at = multisig
+                .get_pending_recovery()
+                .and_then(|recovery| recovery.executable_at)
+                .expect("The quorum is reached");
+            set_block_timestamp::<Environment>(executable_at);
+            assert!(multisig.execute_recovery().is_ok());
+            assert!(multisig.is_paused());
+            assert_eq!(multisig.get_owners(), vec![accounts.frank]);
+        }
+
+        #[ink::test]
+        fn delegate_call_snapshot_covers_the_entries_the_checks_rely_on() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0])).is_ok());
+            let snapshot = multisig.mappings_snapshot();
+
+            // A vote added to an active transaction is detected
+            let votes = multisig.votes.get(0).expect("The transaction is active");
+            let mut forged_votes = votes.clone();
+            forged_votes.record(1, true);
+            multisig.votes.insert(0, &forged_votes);
+            assert_ne!(multisig.mappings_snapshot(), snapshot);
+            multisig.votes.insert(0, &votes);
+
+            // A delegation added to an owner is detected
+            let delegation = Delegation {
+                delegate: accounts.eve,
+                expires_at: 100,
+            };
+            multisig.delegations.insert(accounts.bob, &delegation);
+            assert_ne!(multisig.mappings_snapshot(), snapshot);
+            multisig.delegations.remove(accounts.bob);
+            assert_eq!(multisig.mappings_snapshot(), snapshot);
+
+            // A transaction added out of the active ones is not valid
+            let mut approved = TxVotes::new(accounts.alice);
+            approved.record(0, true);
+            approved.record(1, true);
+            multisig.txs.insert(5, &runtime_call_tx(vec![1]));
+            multisig.votes.insert(5, &approved);
+            assert!(matches!(
+                multisig.is_tx_valid(5),
+                Err(MultisigError::InvalidTxId)
+            ));
+            assert!(matches!(
+                multisig.try_execute_tx(5),
+                Err(MultisigError::InvalidTxId)
+            ));
+
+            // A delegation added to a non owner does not let its delegate vote
+            multisig.delegations.insert(accounts.charlie, &delegation);
+            multisig.delegators.insert(accounts.eve, &accounts.charlie);
+            set_caller::<Environment>(accounts.eve);
+            assert!(matches!(
+                multisig.approve_tx(0),
+                Err(MultisigError::NotOwner)
+            ));
+
+            // and it is cleared when the account becomes an owner
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.add_owner(accounts.charlie).is_ok());
+            assert!(multisig.get_delegation(accounts.charlie).is_none());
+
+            // Stale votes of the next transaction id are replaced when it is proposed
+            multisig.votes.insert(1, &approved);
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![2])).is_ok());
+            assert_eq!(multisig.get_tx_approvals(1), Some(1));
+        }
+
+        #[ink::test]
+        fn allowlist_checks_transfer_receivers_and_is_checked_again_on_execution() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+
+            // A runtime call proposed before the allowlist is enabled
+            let call = vec![0, 1];
+            assert!(multisig.propose_tx(runtime_call_tx(call.clone())).is_ok());
+
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.set_destination_allowlist_enabled(true).is_ok());
+            assert!(multisig.allow_destination(accounts.django).is_ok());
+            assert!(multisig.enable_module(accounts.frank).is_ok());
+
+            // Transfers are self calls, but their receiver must be in the allowlist
+            let transfer = |to: AccountId| Transaction {
+                address: callee::<Environment>(),
+                selector: TRANSFER_SELECTOR,
+                input: scale::Encode::encode(&(to, 1000 as Balance)),
+                transferred_value: 0,
+                ref_time_limit: 0,
+                allow_reentry: false,
+                proof_size_limit: 0,
+                storage_deposit_limit: None,
+                call_type: CallType::Call,
+            };
+            set_caller::<Environment>(accounts.alice);
+            assert!(matches!(
+                multisig.propose_tx(transfer(accounts.eve)),
+                Err(MultisigError::DestinationNotAllowed)
+            ));
+            assert!(multisig.propose_tx(transfer(accounts.django)).is_ok());
+
+            // Runtime calls and staking helpers have no destination, so they are refused
+            assert!(matches!(
+                multisig.propose_tx(runtime_call_tx(call.clone())),
+                Err(MultisigError::DestinationNotAllowed)
+            ));
+            let mut join_pool = transfer(accounts.django);
+            join_pool.selector = ink::selector_bytes!("join_pool");
+            assert!(matches!(
+                multisig.propose_tx(join_pool),
+                Err(MultisigError::DestinationNotAllowed)
+            ));
+
+            // The same checks apply to the transactions of the modules
+            set_caller::<Environment>(accounts.frank);
+            assert!(matches!(
+                multisig.exec_tx_from_module(runtime_call_tx(call.clone())),
+                Err(MultisigError::DestinationNotAllowed)
+            ));
+
+            // The runtime call proposed before is not dispatched when it is executed
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.approve_tx(0).is_ok());
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Failed(MultisigError::DestinationNotAllowed)
+            ));
+            assert!(mocked_runtime::dispatched().is_empty());
+            assert!(multisig.is_tx_valid(0).is_err());
+        }
+
+        #[ink::test]
+        fn modules_are_enabled_disabled_and_execute_checked_transactions() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            let call = vec![0, 1];
+
+            // Only the multisig can enable a module
+            assert!(matches!(
+                multisig.enable_module(accounts.frank),
+                Err(MultisigError::Unauthorized)
+            ));
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.enable_module(accounts.frank).is_ok());
+            assert!(matches!(
+                multisig.enable_module(accounts.frank),
+                Err(MultisigError::ModuleAlreadyEnabled)
+            ));
+            assert!(multisig.is_module(accounts.frank));
+
+            // Other accounts cannot execute transactions
+            set_caller::<Environment>(accounts.eve);
+            assert!(matches!(
+                multisig.exec_tx_from_module(runtime_call_tx(call.clone())),
+                Err(MultisigError::NotModule)
+            ));
+
+            // The module executes a transaction without approvals and it takes a transaction id
+            set_caller::<Environment>(accounts.frank);
+            assert!(matches!(
+                multisig.exec_tx_from_module(runtime_call_tx(call.clone())),
+                Ok(TxResult::Success(_))
+            ));
+            assert_eq!(mocked_runtime::dispatched(), vec![call.clone()]);
+            assert_eq!(multisig.get_next_tx_id(), 1);
+            let executed = emitted_events::<ModuleTransactionExecuted>()
+                .pop()
+                .expect("The module transaction has been executed");
+            assert_eq!(executed.tx_id, 0);
+            assert_eq!(executed.module, accounts.frank);
+            assert!(matches!(executed.result, TxResult::Success(_)));
+
+            // A denied selector is refused even if the transaction requires its minimum threshold
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.deny_selector([0; 4], 1).is_ok());
+            set_caller::<Environment>(accounts.frank);
+            assert!(matches!(
+                multisig.exec_tx_from_module(runtime_call_tx(call.clone())),
+                Err(MultisigError::SelectorDenied)
+            ));
+
+            // A disabled module cannot execute transactions anymore
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.allow_selector([0; 4]).is_ok());
+            assert!(multisig.disable_module(accounts.frank).is_ok());
+            assert!(matches!(
+                multisig.disable_module(accounts.frank),
+                Err(MultisigError::NotModule)
+            ));
+            set_caller::<Environment>(accounts.frank);
+            assert!(matches!(
+                multisig.exec_tx_from_module(runtime_call_tx(call)),
+                Err(MultisigError::NotModule)
+            ));
+            assert_eq!(mocked_runtime::dispatched().len(), 1);
+        }
+
+        #[ink::test]
+        fn guard_approved_transactions_are_performed_in_a_nested_call() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+            multisig.guard = Some(accounts.django);
+            let call = vec![0, 1];
+
+            assert!(multisig.propose_tx(runtime_call_tx(call.clone())).is_ok());
+            assert!(matches!(last_execution_result(), TxResult::Success(_)));
+            assert_eq!(mocked_runtime::dispatched(), vec![call]);
+            assert_eq!(
+                mocked_guard::calls(),
+                vec![
+                    (0, GUARD_CHECK_TRANSACTION_SELECTOR),
+                    (0, PERFORM_GUARDED_TX_SELECTOR),
+                    (0, GUARD_CHECK_AFTER_EXECUTION_SELECTOR),
+                ]
+            );
+            assert!(multisig.is_tx_valid(0).is_err());
+            assert_eq!(multisig.guarded_tx, None);
+
+            // The nested call only performs the transaction the multisig is performing
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(matches!(
+                multisig.perform_guarded_tx(1, runtime_call_tx(vec![2])),
+                Err(MultisigError::Unauthorized)
+            ));
+        }
+
+        #[ink::test]
+        fn guard_rejected_transactions_are_failed_and_reverted() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+            multisig.guard = Some(accounts.django);
+            let call = vec![0, 1];
+
+            // A rejection before the call marks the transaction as failed without performing it
+            mocked_guard::set_approving(false, true);
+            assert!(multisig.propose_tx(runtime_call_tx(call.clone())).is_ok());
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Failed(MultisigError::GuardRejected)
+            ));
+            assert!(mocked_runtime::dispatched().is_empty());
+            assert_eq!(
+                mocked_guard::calls(),
+                vec![(0, GUARD_CHECK_TRANSACTION_SELECTOR)]
+            );
+
+            // A rejection after the call reverts it and marks the transaction as failed
+            mocked_guard::set_approving(true, false);
+            assert!(multisig.propose_tx(runtime_call_tx(call)).is_ok());
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Failed(MultisigError::GuardRejected)
+            ));
+            assert!(mocked_runtime::dispatched().is_empty());
+            assert_eq!(
+                mocked_guard::calls()[1..],
+                [
+                    (1, GUARD_CHECK_TRANSACTION_SELECTOR),
+                    (1, PERFORM_GUARDED_TX_SELECTOR),
+                    (1, GUARD_CHECK_AFTER_EXECUTION_SELECTOR),
+                ]
+            );
+            assert!(multisig.is_tx_valid(1).is_err());
+            assert_eq!(multisig.guarded_tx, None);
+        }
+
+        #[ink::test]
+        fn guard_calls_that_revert_are_rejections() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+            multisig.guard = Some(accounts.django);
+            let call = vec![0, 1];
+
+            // A guard that reverts when checking the transaction does not let it be performed
+            mocked_guard::set_replies(None, Some(true));
+            assert!(multisig.propose_tx(runtime_call_tx(call.clone())).is_ok());
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Failed(MultisigError::GuardRejected)
+            ));
+            assert!(mocked_runtime::dispatched().is_empty());
+
+            // A guard that reverts when checking the outcome reverts the transaction
+            mocked_guard::set_replies(Some(true), None);
+            assert!(multisig.propose_tx(runtime_call_tx(call)).is_ok());
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Failed(MultisigError::GuardRejected)
+            ));
+            assert!(mocked_runtime::dispatched().is_empty());
+            assert_eq!(multisig.guarded_tx, None);
+        }
+
+        #[ink::test]
+        fn threshold_policies_are_matched_by_value_range() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(1, vec![accounts.alice, accounts.bob, accounts.charlie]);
+            let range = |min_value, max_value, threshold| ThresholdPolicyRange {
+                min_value,
+                max_value,
+                threshold,
+            };
+            let tx_with_value = |transferred_value| Transaction {
+                transferred_value,
+                ..runtime_call_tx(vec![0, 1])
+            };
+
+            // Overlapping ranges are refused, including the ones sharing a bound
+            set_caller::<Environment>(callee::<Environment>());
+            for ranges in [
+                vec![range(0, Some(1000), 1), range(500, None, 3)],
+                vec![range(0, Some(1000), 1), range(1000, None, 3)],
+                vec![range(1000, None, 3), range(0, None, 1)],
+            ] {
+                assert!(matches!(
+                    multisig.set_threshold_policy(accounts.django, [0; 4], ranges),
+                    Err(MultisigError::InvalidPolicyRanges)
+                ));
+            }
+
+            // The ranges are kept sorted whatever order they are given in
+            assert!(multisig
+                .set_threshold_policy(
+                    accounts.django,
+                    [0; 4],
+                    vec![range(1000, None, 3), range(0, Some(99), 1)],
+                )
+                .is_ok());
+            let policy = multisig
+                .get_threshold_policy(accounts.django, [0; 4])
+                .expect("The policy has been set");
+            assert_eq!(
+                policy
+                    .iter()
+                    .map(|range| (range.min_value, range.max_value))
+                    .collect::<Vec<_>>(),
+                vec![(0, Some(99)), (1000, None)]
+            );
+
+            // A value within the bounded range is executed with its threshold when proposed
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(tx_with_value(99)).is_ok());
+            assert!(matches!(last_execution_result(), TxResult::Success(_)));
+
+            // A value out of every range uses the default threshold
+            assert!(multisig.propose_tx(tx_with_value(500)).is_ok());
+            assert!(matches!(last_execution_result(), TxResult::Success(_)));
+
+            // The unbounded range matches any value above its minimum
+            assert!(multisig.propose_tx(tx_with_value(Balance::MAX)).is_ok());
+            assert_eq!(multisig.get_tx_threshold(2), Some(3));
+
+            // Its threshold is not met with two approvals
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.approve_tx(2).is_ok());
+            assert!(multisig.is_tx_valid(2).is_ok());
+
+            // and it can't be met once an owner rejects it
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.reject_tx(2).is_ok());
+            assert!(multisig.is_tx_valid(2).is_err());
+            assert_eq!(mocked_runtime::dispatched().len(), 2);
+        }
+
+        #[ink::test]
+        fn admin_self_calls_use_the_threshold_unless_a_stricter_one_is_set() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(1, vec![accounts.alice, accounts.bob, accounts.charlie]);
+
+            // By default admin self calls require the threshold
+            assert_eq!(multisig.get_admin_threshold(), 1);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0])).is_ok());
+            assert!(matches!(last_execution_result(), TxResult::Success(_)));
+
+            // A stricter admin threshold is opt-in
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.change_admin_threshold(Some(2)).is_ok());
+            assert_eq!(multisig.get_admin_threshold(), 2);
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![1])).is_ok());
+            assert_eq!(multisig.get_tx_threshold(1), Some(2));
+            assert_eq!(mocked_runtime::dispatched().len(), 1);
+
+            // Removing it falls back to the threshold
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.change_admin_threshold(None).is_ok());
+            assert_eq!(multisig.get_admin_threshold(), multisig.get_threshold());
+        }
+
+        #[ink::test]
+        fn transactions_encoded_before_the_weight_v2_limits_are_decoded() {
+            let accounts = accounts();
+
+            // The former transactions had a single gas limit as the last limit field
+            let legacy = scale::Encode::encode(&(
+                accounts.bob,
+                [1u8; 4],
+                vec![2u8],
+                3 as Balance,
+                4u64,
+                true,
+            ));
+            let tx = Transaction::decode(&mut &legacy[..]).expect("The transaction is decodable");
+            assert_eq!(tx.address, accounts.bob);
+            assert_eq!(tx.input, vec![2]);
+            assert_eq!(tx.transferred_value, 3);
+            assert_eq!(tx.ref_time_limit, 4);
+            assert!(tx.allow_reentry);
+            assert_eq!(tx.proof_size_limit, 0);
+            assert_eq!(tx.storage_deposit_limit, None);
+            assert!(tx.call_type == CallType::Call);
+
+            // The limits of the current transactions are kept
+            let mut tx = runtime_call_tx(vec![5]);
+            tx.proof_size_limit = 6;
+            tx.storage_deposit_limit = Some(7);
+            let encoded = scale::Encode::encode(&tx);
+            let tx = Transaction::decode(&mut &encoded[..]).expect("The transaction is decodable");
+            assert_eq!(tx.proof_size_limit, 6);
+            assert_eq!(tx.storage_deposit_limit, Some(7));
+            assert!(tx.call_type == CallType::RuntimeCall);
+        }
+
+        #[ink::test]
+        fn delegate_code_hashes_report_their_storage_layout() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+            let current = Hash::from([1; 32]);
+            let outdated = Hash::from([2; 32]);
+            let unknown = Hash::from([3; 32]);
+            register_script(current, STORAGE_LAYOUT_VERSION, || {});
+            register_script(outdated, STORAGE_LAYOUT_VERSION - 1, || {});
+            multisig.write_root();
+
+            // The version is reported by the code, not by the proposers
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.allow_delegate_code_hash(current).is_ok());
+            assert_eq!(
+                multisig.get_delegate_code_hash(current),
+                Some(STORAGE_LAYOUT_VERSION)
+            );
+            assert!(matches!(
+                multisig.allow_delegate_code_hash(outdated),
+                Err(MultisigError::IncompatibleStorageLayout)
+            ));
+            assert!(matches!(
+                multisig.allow_delegate_code_hash(unknown),
+                Err(MultisigError::IncompatibleStorageLayout)
+            ));
+            assert_eq!(multisig.get_delegate_code_hash(outdated), None);
+            assert_eq!(multisig.get_delegate_code_hash(unknown), None);
+        }
+
+        #[ink::test]
+        fn delegate_calls_that_modify_the_storage_or_the_code_are_aborted() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+
+            // A staking record and a delegation that no check of the root covers
+            multisig.staking_records.insert(
+                0,
+                &StakingRecord {
+                    operation: StakingOperation::ClaimPoolPayout,
+                    block_number: 0,
+                    timestamp: 0,
+                },
+            );
+            multisig.staking_records_count = 1;
+            assert!(multisig.delegate_votes(accounts.eve, 100).is_ok());
+
+            let harmless = Hash::from([1; 32]);
+            let records = Hash::from([2; 32]);
+            let delegators = Hash::from([3; 32]);
+            let code = Hash::from([4; 32]);
+            register_script(harmless, STORAGE_LAYOUT_VERSION, || {});
+            register_script(records, STORAGE_LAYOUT_VERSION, || {
+                stored_multisig().staking_records.insert(
+                    0,
+                    &StakingRecord {
+                        operation: StakingOperation::Unbond { value: 1 },
+                        block_number: 0,
+                        timestamp: 0,
+                    },
+                );
+            });
+            let (eve, bob) = (accounts.eve, accounts.bob);
+            register_script(delegators, STORAGE_LAYOUT_VERSION, move || {
+                stored_multisig().delegators.insert(eve, &bob);
+            });
+            register_script(code, STORAGE_LAYOUT_VERSION, || {
+                mocked_scripts::set_own_code_hash(Hash::from([0x22; 32]));
+            });
+            multisig.write_root();
+            set_caller::<Environment>(callee::<Environment>());
+            for code_hash in [harmless, records, delegators, code] {
+                assert!(multisig.allow_delegate_code_hash(code_hash).is_ok());
+            }
+
+            let delegate_tx = |code_hash| Transaction {
+                address: accounts.django,
+                selector: [0; 4],
+                input: Vec::new(),
+                transferred_value: 0,
+                ref_time_limit: 0,
+                allow_reentry: false,
+                proof_size_limit: 0,
+                storage_deposit_limit: None,
+                call_type: CallType::DelegateCall(code_hash),
+            };
+
+            // A script that leaves the storage untouched is executed
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(delegate_tx(harmless)).is_ok());
+            assert!(matches!(last_execution_result(), TxResult::Success(_)));
+
+            // Any other script aborts the whole execution, so its changes are reverted
+            for code_hash in [records, delegators, code] {
+                assert!(matches!(
+                    multisig.propose_tx(delegate_tx(code_hash)),
+                    Err(MultisigError::StorageCorrupted)
+                ));
+            }
+        }
+
+        #[ink::test]
+        fn transactions_encoded_before_the_call_type_are_regular_calls() {
+            let accounts = accounts();
+
+            // The transactions with the Weight v2 limits and without the call type
+            let encoded = scale::Encode::encode(&(
+                accounts.bob,
+                [1u8; 4],
+                Vec::<u8>::new(),
+                0 as Balance,
+                2u64,
+                false,
+                3u64,
+                Some(4 as Balance),
+            ));
+            let tx = Transaction::decode(&mut &encoded[..]).expect("The transaction is decodable");
+            assert_eq!(tx.ref_time_limit, 2);
+            assert_eq!(tx.proof_size_limit, 3);
+            assert_eq!(tx.storage_deposit_limit, Some(4));
+            assert!(tx.call_type == CallType::Call);
+        }
+
+        #[ink::test]
+        fn denied_selectors_stay_callable_after_owners_are_removed() {
+            let accounts = accounts();
+            let mut multisig =
+                new_multisig(2, vec![accounts.alice, accounts.bob, accounts.charlie]);
+            let selector = [1; 4];
+            let tx = || Transaction {
+                address: accounts.django,
+                selector,
+                input: Vec::new(),
+                transferred_value: 0,
+                ref_time_limit: 0,
+                allow_reentry: false,
+                proof_size_limit: 0,
+                storage_deposit_limit: None,
+                call_type: CallType::Call,
+            };
+
+            // The selector requires every owner, so a policy raises the threshold of its calls
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.deny_selector(selector, 3).is_ok());
+            set_caller::<Environment>(accounts.alice);
+            assert!(matches!(
+                multisig.propose_tx(tx()),
+                Err(MultisigError::SelectorDenied)
+            ));
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig
+                .set_threshold_policy(
+                    accounts.django,
+                    selector,
+                    vec![ThresholdPolicyRange {
+                        min_value: 0,
+                        max_value: None,
+                        threshold: 3,
+                    }],
+                )
+                .is_ok());
+
+            // Once an owner is removed neither threshold can be met, so both are capped
+            assert!(multisig.remove_owner(accounts.charlie).is_ok());
+            assert_eq!(multisig.get_denied_selector(selector), Some(2));
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.propose_tx(tx()).is_ok());
+            assert_eq!(multisig.get_tx_threshold(0), Some(2));
+        }
+
+        #[ink::test]
+        fn simulations_return_the_result_with_the_revert_flag() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            let call = vec![0, 1];
+            let simulation = |returned: mocked_execution::Returned| {
+                assert_eq!(returned.flags, ReturnFlags::REVERT);
+                match <Result<Result<TxSimulation, MultisigError>, LangError>>::decode(
+                    &mut &returned.value[..],
+                ) {
+                    Ok(Ok(Ok(simulation))) => simulation,
+                    _ => panic!("The simulation is returned"),
+                }
+            };
+
+            // A pending transaction is simulated regardless of its approvals
+            assert!(multisig.propose_tx(runtime_call_tx(call.clone())).is_ok());
+            let returned = mocked_execution::returned(|| {
+                let _ = multisig.simulate_tx(0);
+            });
+            let pending = simulation(returned);
+            assert!(matches!(pending.result, TxResult::Success(_)));
+            assert_eq!(pending.gas_consumed, mocked_execution::GAS_PER_READING);
+
+            // A candidate transaction is simulated as the next one, failures included
+            mocked_runtime::set_failing(true);
+            let returned = mocked_execution::returned(|| {
+                let _ = multisig.simulate_transaction(runtime_call_tx(call.clone()));
+            });
+            assert!(matches!(
+                simulation(returned).result,
+                TxResult::Failed(MultisigError::EnvExecutionFailed(_))
+            ));
+        }
+
+        #[ink::test]
+        fn instantiation_results_are_decoded() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+            let code_hash = Hash::from([5; 32]);
+            let instantiate_tx = || Transaction {
+                address: accounts.django,
+                selector: [9; 4],
+                input: Vec::new(),
+                transferred_value: 0,
+                ref_time_limit: 0,
+                allow_reentry: false,
+                proof_size_limit: 0,
+                storage_deposit_limit: None,
+                call_type: CallType::Instantiate {
+                    code_hash,
+                    salt: vec![1],
+                },
+            };
+
+            // The address of the new contract is the result of the transaction
+            mocked_instantiation::set_deployed(accounts.frank);
+            assert!(multisig.propose_tx(instantiate_tx()).is_ok());
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Instantiated(contract) if contract == accounts.frank
+            ));
+            let instantiated = emitted_events::<ContractInstantiated>();
+            assert_eq!(instantiated.len(), 1);
+            assert_eq!(instantiated[0].tx_id, 0);
+            assert_eq!(instantiated[0].contract, accounts.frank);
+            assert_eq!(instantiated[0].code_hash, code_hash);
+
+            // The error of a fallible constructor is kept encoded
+            let error = String::from("InsufficientSupply");
+            mocked_instantiation::set_reverted(
+                Ok::<Result<(), String>, LangError>(Err(error.clone())).encode(),
+            );
+            assert!(multisig.propose_tx(instantiate_tx()).is_ok());
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Failed(MultisigError::ConstructorFailed(output)) if output == error.encode()
+            ));
+
+            // as the errors of the contract language are
+            mocked_instantiation::set_reverted(
+                Err::<Result<(), String>, LangError>(LangError::CouldNotReadInput).encode(),
+            );
+            assert!(multisig.propose_tx(instantiate_tx()).is_ok());
+            assert!(matches!(
+                last_execution_result(),
+                TxResult::Failed(MultisigError::LangExecutionFailed(
+                    LangError::CouldNotReadInput
+                ))
+            ));
+            assert_eq!(emitted_events::<ContractInstantiated>().len(), 1);
+        }
+
+        #[ink::test]
+        fn reentrant_self_calls_are_kept_when_the_root_is_read_again() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(1, vec![accounts.alice]);
+            let self_call = |selector: &str, input: Vec<u8>| Transaction {
+                address: callee::<Environment>(),
+                selector: match selector {
+                    "add_owner" => ink::selector_bytes!("add_owner"),
+                    "remove_owner" => ink::selector_bytes!("remove_owner"),
+                    _ => ink::selector_bytes!("change_threshold"),
+                },
+                input,
+                transferred_value: 0,
+                ref_time_limit: 0,
+                allow_reentry: true,
+                proof_size_limit: 0,
+                storage_deposit_limit: None,
+                call_type: CallType::Call,
+            };
+
+            // Each transaction reenters the contract while it is being executed
+            assert!(multisig
+                .propose_tx(self_call("add_owner", accounts.bob.encode()))
+                .is_ok());
+            assert!(matches!(last_execution_result(), TxResult::Success(_)));
+            assert_eq!(multisig.get_owners(), vec![accounts.alice, accounts.bob]);
+            assert!(multisig
+                .propose_tx(self_call("add_owner", accounts.charlie.encode()))
+                .is_ok());
+            assert!(multisig
+                .propose_tx(self_call("change_threshold", 2u8.encode()))
+                .is_ok());
+            assert_eq!(multisig.get_threshold(), 2);
+
+            // The threshold of 2 is met with Bob's approval
+            assert!(multisig
+                .propose_tx(self_call("remove_owner", accounts.alice.encode()))
+                .is_ok());
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.approve_tx(3).is_ok());
+            assert!(matches!(last_execution_result(), TxResult::Success(_)));
+
+            // Every change of the sequence is kept, none is overwritten by the stale state
+            // of the executing message, which has been updated as well
+            assert_eq!(multisig.get_owners(), vec![accounts.bob, accounts.charlie]);
+            assert!(!multisig.is_owner(accounts.alice));
+            assert_eq!(multisig.get_threshold(), 2);
+            assert_eq!(multisig.get_next_tx_id(), 4);
+            assert_eq!(multisig.get_active_txs_count(), 0);
+
+            // The root written at the end of the outer message has the same state
+            multisig.write_root();
+            let stored = stored_multisig();
+            assert_eq!(stored.get_owners(), vec![accounts.bob, accounts.charlie]);
+            assert_eq!(stored.get_threshold(), 2);
+        }
+
+        #[ink::test]
+        fn beneficiaries_sweep_the_balance_above_the_minimum_despite_delegate_votes() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig
+                .set_inheritance_config(Some(InheritanceConfig {
+                    beneficiaries: vec![accounts.charlie],
+                    inactivity_period: 10,
+                    action: InheritanceAction::Sweep(accounts.eve),
+                }))
+                .is_ok());
+            let minimum_balance = ink::env::minimum_balance::<Environment>();
+            set_account_balance::<Environment>(callee::<Environment>(), minimum_balance + 1_000);
+            set_account_balance::<Environment>(accounts.eve, 0);
+
+            // Bob proposes a transaction and Django approves it on behalf of Alice
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.delegate_votes(accounts.django, 100).is_ok());
+            set_caller::<Environment>(accounts.bob);
+            assert!(multisig.propose_tx(runtime_call_tx(vec![0, 0])).is_ok());
+            for _ in 0..5 {
+                ink::env::test::advance_block::<Environment>();
+            }
+            set_caller::<Environment>(accounts.django);
+            assert!(multisig.approve_tx(0).is_ok());
+            assert!(matches!(last_execution_result(), TxResult::Success(_)));
+
+            // The delegate vote does not postpone the claim
+            assert_eq!(multisig.get_owner_last_activity(accounts.alice), None);
+            assert_eq!(multisig.get_inheritance_claimable_at(), Some(10));
+            for _ in 0..5 {
+                ink::env::test::advance_block::<Environment>();
+            }
+
+            // The multisig account keeps the minimum balance
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.claim_inheritance().is_ok());
+            assert_eq!(
+                get_account_balance::<Environment>(accounts.eve).ok(),
+                Some(1_000)
+            );
+            assert_eq!(
+                get_account_balance::<Environment>(callee::<Environment>()).ok(),
+                Some(minimum_balance)
+            );
+        }
+
+        #[ink::test]
+        fn pause_guardian_cannot_pause_again_until_the_cooldown_elapses() {
+            let accounts = accounts();
+            let mut multisig = new_multisig(2, vec![accounts.alice, accounts.bob]);
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.set_pause_guardian(Some(accounts.charlie)).is_ok());
+            assert!(multisig.set_pause_duration(3).is_ok());
+
+            // The pause guardian pauses the wallet until block 3
+            set_caller::<Environment>(accounts.charlie);
+            assert!(multisig.pause().is_ok());
+            for _ in 0..3 {
+                ink::env::test::advance_block::<Environment>();
+            }
+            assert!(!multisig.is_paused());
+
+            // It cannot pause again right after the pause expires
+            assert!(matches!(
+                multisig.pause(),
+                Err(MultisigError::PauseCooldown)
+            ));
+            assert_eq!(multisig.get_paused_until(), Some(3));
+
+            // The owners can still pause the wallet, and unpause it
+            set_caller::<Environment>(accounts.alice);
+            assert!(multisig.pause().is_ok());
+            assert_eq!(multisig.get_paused_until(), Some(6));
+            set_caller::<Environment>(callee::<Environment>());
+            assert!(multisig.unpause().is_ok());
+            assert_eq!(multisig.get_paused_until(), Some(3));
+
+            // The cooldown of the pause guardian starts when the pause is lifted
+            for _ in 0..2 {
+                ink::env::test::advance_block::<Environment>();
+            }
+            set_caller::<Environment>(accounts.charlie);
+            assert!(matches!(
+                multisig.pause(),
+                Err(MultisigError::PauseCooldown)
+            ));
+            ink::env::test::advance_block::<Environment>();
+            assert!(multisig.pause().is_ok());
+            assert_eq!(multisig.get_paused_until(), Some(9));
+        }
     }
 }
@@ -0,0 +1,121 @@
+import { expect } from "chai";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import ContractAbi from "../../artifacts/multisig/multisig.json";
+import { MessageIndex } from "../utils/MessageIndex";
+import { assignKeyringPairs, buildTransaction } from "../utils/testHelpers";
+import Contract from "../../typed_contracts/multisig/contracts/multisig";
+import Constructors from "../../typed_contracts/multisig/constructors/multisig";
+
+let api;
+let keyring;
+let aliceKeyringPair;
+let bobKeyringPair;
+let multisigMessageIndex;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+describe("Delegate Call Transactions", () => {
+  before(() => {
+    // call function to create keyring pairs
+    [aliceKeyringPair, bobKeyringPair] = assignKeyringPairs(keyring, 2);
+    // Index that allows to get the selector of a message by its label
+    multisigMessageIndex = new MessageIndex(ContractAbi);
+  });
+
+  it("Should refuse delegate calls to code hashes that are not allowed", async () => {
+    // Create a new contract
+    const constructors = new Constructors(api, aliceKeyringPair);
+    const { address } = await constructors.new(1, [aliceKeyringPair.address]);
+    expect(address).to.exist;
+
+    // Bind the contract to the new address
+    const multisig = new Contract(address, aliceKeyringPair, api);
+
+    const codeHash = multisigMessageIndex.getCodeHash();
+
+    // No code hash is allowed by default
+    const layoutVersion = (
+      await multisig.query.getDelegateCodeHash(codeHash)
+    ).value.ok;
+    expect(layoutVersion).to.not.exist;
+
+    const transferTx = await buildTransaction(
+      api,
+      address,
+      "transfer",
+      [bobKeyringPair.address, 1000],
+      multisigMessageIndex
+    );
+    const delegateTx = { ...transferTx, callType: { delegateCall: codeHash } };
+
+    // The proposal is refused and does not take a slot
+    const proposeResult = (await multisig.query.proposeTx(delegateTx)).value
+      .ok;
+    expect(Object.keys(proposeResult.err)).to.include("delegateCallNotAllowed");
+
+    const nextTxId = (await multisig.query.getNextTxId()).value
+      .unwrap()
+      .toNumber();
+    expect(nextTxId).to.equal(0);
+  });
+
+  it("Should not allow code hashes that do not report the storage layout", async () => {
+    // Create a new contract
+    const constructors = new Constructors(api, aliceKeyringPair);
+    const { address } = await constructors.new(1, [aliceKeyringPair.address]);
+    expect(address).to.exist;
+
+    // Bind the contract to the new address
+    const multisig = new Contract(address, aliceKeyringPair, api);
+
+    // The multisig code does not implement the storage_layout_version message
+    const codeHash = multisigMessageIndex.getCodeHash();
+
+    const allowCodeHashTx = await buildTransaction(
+      api,
+      address,
+      "allow_delegate_code_hash",
+      [codeHash],
+      multisigMessageIndex
+    );
+
+    //Listen for the event
+    let newTxExecutedEvent;
+    multisig.events.subscribeOnTransactionExecutedEvent((event) => {
+      newTxExecutedEvent = event;
+    });
+
+    // Propose the transaction on chain, it is executed because the threshold is 1
+    await multisig.tx.proposeTx(allowCodeHashTx);
+
+    expect(newTxExecutedEvent).to.exist;
+    expect(Object.keys(newTxExecutedEvent.result)).to.include("failed");
+
+    const layoutVersion = (
+      await multisig.query.getDelegateCodeHash(codeHash)
+    ).value.ok;
+    expect(layoutVersion).to.not.exist;
+  });
+});
@@ -0,0 +1,114 @@
+import { expect } from "chai";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import ContractAbi from "../../artifacts/multisig/multisig.json";
+import { MessageIndex } from "../utils/MessageIndex";
+import { assignKeyringPairs, buildTransaction } from "../utils/testHelpers";
+import Contract from "../../typed_contracts/multisig/contracts/multisig";
+import Constructors from "../../typed_contracts/multisig/constructors/multisig";
+
+let api;
+let keyring;
+let aliceKeyringPair;
+let bobKeyringPair;
+let multisigMessageIndex;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+describe("Destination Allowlist", () => {
+  before(() => {
+    // call function to create keyring pairs
+    [aliceKeyringPair, bobKeyringPair] = assignKeyringPairs(keyring, 2);
+    // Index that allows to get the selector of a message by its label
+    multisigMessageIndex = new MessageIndex(ContractAbi);
+  });
+
+  it("Should refuse proposals to destinations out of the allowlist", async () => {
+    // Create a new contract
+    const constructors = new Constructors(api, aliceKeyringPair);
+    const { address } = await constructors.new(1, [aliceKeyringPair.address]);
+    expect(address).to.exist;
+
+    // Bind the contract to the new address
+    const multisig = new Contract(address, aliceKeyringPair, api);
+
+    // Every destination is allowed by default
+    let isBobAllowed = (
+      await multisig.query.isDestinationAllowed(bobKeyringPair.address)
+    ).value.unwrap();
+    expect(isBobAllowed).to.be.true;
+
+    const enableAllowlistTx = await buildTransaction(
+      api,
+      address,
+      "set_destination_allowlist_enabled",
+      [true],
+      multisigMessageIndex
+    );
+
+    // Propose the transaction on chain, it is executed because the threshold is 1
+    await multisig.tx.proposeTx(enableAllowlistTx);
+
+    const isAllowlistEnabled = (
+      await multisig.query.isDestinationAllowlistEnabled()
+    ).value.unwrap();
+    expect(isAllowlistEnabled).to.be.true;
+
+    // Bob is not in the allowlist anymore, but the multisig itself is
+    isBobAllowed = (
+      await multisig.query.isDestinationAllowed(bobKeyringPair.address)
+    ).value.unwrap();
+    expect(isBobAllowed).to.be.false;
+
+    const isSelfAllowed = (
+      await multisig.query.isDestinationAllowed(address)
+    ).value.unwrap();
+    expect(isSelfAllowed).to.be.true;
+
+    // A proposal to Bob is refused and does not take a slot
+    const txToBob = { ...enableAllowlistTx, address: bobKeyringPair.address };
+    const proposeResult = (await multisig.query.proposeTx(txToBob)).value.ok;
+    expect(Object.keys(proposeResult.err)).to.include("destinationNotAllowed");
+
+    const activeTxs = (
+      await multisig.query.getActiveTxidList()
+    ).value.unwrap();
+    expect(activeTxs).to.have.lengthOf(0);
+
+    // Once Bob is allowed the proposal is accepted
+    const allowBobTx = await buildTransaction(
+      api,
+      address,
+      "allow_destination",
+      [bobKeyringPair.address],
+      multisigMessageIndex
+    );
+    await multisig.tx.proposeTx(allowBobTx);
+
+    isBobAllowed = (
+      await multisig.query.isDestinationAllowed(bobKeyringPair.address)
+    ).value.unwrap();
+    expect(isBobAllowed).to.be.true;
+  });
+});
@@ -0,0 +1,129 @@
+import { expect } from "chai";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import ContractAbi from "../../artifacts/multisig/multisig.json";
+import { MessageIndex } from "../utils/MessageIndex";
+import {
+  assignKeyringPairs,
+  createABCMultiSigAndEnsureState,
+  buildTransaction,
+} from "../utils/testHelpers";
+
+let api;
+let keyring;
+let aliceKeyringPair;
+let bobKeyringPair;
+let charlieKeyringPair;
+let daveKeyringPair;
+let keypairs;
+let multisigMessageIndex;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+describe("Emergency Pause", () => {
+  before(() => {
+    // call function to create keyring pairs
+    keypairs = assignKeyringPairs(keyring, 4);
+    [aliceKeyringPair, bobKeyringPair, charlieKeyringPair, daveKeyringPair] =
+      keypairs;
+    // Index that allows to get the selector of a message by its label
+    multisigMessageIndex = new MessageIndex(ContractAbi);
+  });
+
+  it("Should block proposals until the owners unpause the wallet", async () => {
+    // Create a new contract with threshold 2
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs,
+      2
+    );
+
+    // A single owner pauses the wallet
+    await multisig.withSigner(bobKeyringPair).tx.pause();
+
+    let isPaused = (await multisig.query.isPaused()).value.unwrap();
+    expect(isPaused).to.be.true;
+
+    // Regular proposals are refused while paused
+    const addOwnerTx = await buildTransaction(
+      api,
+      address,
+      "add_owner",
+      [daveKeyringPair.address],
+      multisigMessageIndex
+    );
+    const proposeResult = (await multisig.query.proposeTx(addOwnerTx)).value
+      .ok;
+    expect(Object.keys(proposeResult.err)).to.include("paused");
+
+    // The unpause self call can be proposed and executed with the threshold
+    const unpauseTx = await buildTransaction(
+      api,
+      address,
+      "unpause",
+      [],
+      multisigMessageIndex
+    );
+    await multisig.tx.proposeTx(unpauseTx);
+
+    //Listen for the event
+    let newTxExecutedEvent;
+    multisig.events.subscribeOnTransactionExecutedEvent((event) => {
+      newTxExecutedEvent = event;
+    });
+
+    // Approve the transaction by Charlie
+    await multisig.withSigner(charlieKeyringPair).tx.approveTx(0);
+
+    // Emit the success in the event result
+    expect(newTxExecutedEvent).to.exist;
+    expect(Object.keys(newTxExecutedEvent.result)).to.include("success");
+
+    isPaused = (await multisig.query.isPaused()).value.unwrap();
+    expect(isPaused).to.be.false;
+
+    // Proposals are accepted again
+    await multisig.tx.proposeTx(addOwnerTx);
+    const tx_1 = (await multisig.query.getTx(1)).value.ok;
+    expect(tx_1).to.exist;
+  });
+
+  it("Should not allow other accounts to pause the wallet", async () => {
+    // Create a new contract with threshold 2
+    const [, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs,
+      2
+    );
+
+    // Dave is neither an owner nor the pause guardian
+    const pauseResult = (
+      await multisig.withSigner(daveKeyringPair).query.pause()
+    ).value.ok;
+    expect(Object.keys(pauseResult.err)).to.include("unauthorized");
+
+    const isPaused = (await multisig.query.isPaused()).value.unwrap();
+    expect(isPaused).to.be.false;
+  });
+});
@@ -0,0 +1,146 @@
+import { expect } from "chai";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import ContractAbi from "../../artifacts/multisig/multisig.json";
+import { MessageIndex } from "../utils/MessageIndex";
+import { deployExternalContracts } from "../utils/contractsDeployment";
+import {
+  assignKeyringPairs,
+  createABCMultiSigAndEnsureState,
+  buildTransaction,
+} from "../utils/testHelpers";
+import { Transaction } from "../../typed_contracts/multisig/types-arguments/multisig";
+
+let api;
+let keyring;
+let externalContracts;
+let aliceKeyringPair;
+let bobKeyringPair;
+let charlieKeyringPair;
+let daveKeyringPair;
+let keypairs;
+let multisigMessageIndex;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+
+    // Deploy external contracts
+    externalContracts = await deployExternalContracts(api, keyring);
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+// Build the set_guard self call, its Option argument can't be encoded by the message index
+const buildSetGuardTransaction = (multisigAddress, guard) => {
+  const selector =
+    multisigMessageIndex.getMessageInfo("set_guard")?.selector.bytes;
+  const input = Array.from(
+    api.createType("Option<AccountId>", guard).toU8a() as Uint8Array
+  );
+
+  const tx: Transaction = {
+    address: multisigAddress,
+    selector: selector!,
+    input,
+    transferredValue: 0,
+    refTimeLimit: 0,
+    allowReentry: true,
+    proofSizeLimit: 0,
+    storageDepositLimit: null,
+    callType: { call: null },
+  };
+
+  return tx;
+};
+
+describe("Transaction guard", () => {
+  before(() => {
+    // call function to create keyring pairs
+    keypairs = assignKeyringPairs(keyring, 4);
+    [aliceKeyringPair, bobKeyringPair, charlieKeyringPair, daveKeyringPair] =
+      keypairs;
+    // Index that allows to get the selector of a message by its label
+    multisigMessageIndex = new MessageIndex(ContractAbi);
+  });
+
+  it("Should replace a broken guard that rejects every other transaction", async () => {
+    // Create a new contract with threshold 2
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs,
+      2
+    );
+
+    //Listen for the event
+    let newTxExecutedEvent;
+    multisig.events.subscribeOnTransactionExecutedEvent((event) => {
+      newTxExecutedEvent = event;
+    });
+
+    // The PSP22 contract does not implement the guard messages, so it is a broken guard
+    const brokenGuard = externalContracts["psp22.contract"].address;
+    await multisig.tx.proposeTx(
+      buildSetGuardTransaction(address, brokenGuard)
+    );
+    await multisig.withSigner(bobKeyringPair).tx.approveTx(0);
+
+    expect(Object.keys(newTxExecutedEvent.result)).to.include("success");
+    let guard = (await multisig.query.getGuard()).value.unwrap();
+    expect(guard).to.equal(brokenGuard);
+
+    // Every other transaction is rejected by the guard and marked as failed
+    const addOwnerTx = await buildTransaction(
+      api,
+      address,
+      "add_owner",
+      [daveKeyringPair.address],
+      multisigMessageIndex
+    );
+    await multisig.tx.proposeTx(addOwnerTx);
+    await multisig.withSigner(charlieKeyringPair).tx.approveTx(1);
+
+    expect(Object.keys(newTxExecutedEvent.result)).to.include("failed");
+    expect(Object.keys(newTxExecutedEvent.result.failed)).to.include(
+      "guardRejected"
+    );
+    let isOwner = (
+      await multisig.query.isOwner(daveKeyringPair.address)
+    ).value.unwrap();
+    expect(isOwner).to.be.false;
+
+    // The set_guard transaction is not checked by the guard, so it can be removed
+    await multisig.tx.proposeTx(buildSetGuardTransaction(address, null));
+    await multisig.withSigner(bobKeyringPair).tx.approveTx(2);
+
+    expect(Object.keys(newTxExecutedEvent.result)).to.include("success");
+    guard = (await multisig.query.getGuard()).value.unwrap();
+    expect(guard).to.not.exist;
+
+    // The transactions are executed again
+    await multisig.tx.proposeTx(addOwnerTx);
+    await multisig.withSigner(charlieKeyringPair).tx.approveTx(3);
+
+    expect(Object.keys(newTxExecutedEvent.result)).to.include("success");
+    isOwner = (
+      await multisig.query.isOwner(daveKeyringPair.address)
+    ).value.unwrap();
+    expect(isOwner).to.be.true;
+  });
+});
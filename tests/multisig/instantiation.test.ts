@@ -0,0 +1,110 @@
+import { expect } from "chai";
+import Constructors from "../../typed_contracts/multisig/constructors/multisig";
+import Contract from "../../typed_contracts/multisig/contracts/multisig";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import { Transaction } from "../../typed_contracts/multisig/types-arguments/multisig";
+import { hex_to_bytes } from "../utils/convertions";
+import { deployExternalContracts } from "../utils/contractsDeployment";
+import { assignKeyringPairs } from "../utils/testHelpers";
+
+let api;
+let keyring;
+let externalContracts;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+
+    // Deploy external contracts
+    externalContracts = await deployExternalContracts(api, keyring);
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+describe("Contract instantiation", () => {
+  it("Should instantiate a contract owned by the multisig", async () => {
+    const PayableContract = externalContracts["payable_contract.contract"].abi;
+
+    // The payable contract code is already on chain because it was deployed before
+    const codeHash = PayableContract.source.hash;
+    const constructorSelector = hex_to_bytes(
+      PayableContract.spec.constructors.find(
+        (constructor) => constructor.label === "default"
+      ).selector
+    );
+
+    const aliceKeyringPair = assignKeyringPairs(keyring, 1)[0];
+
+    // Create a new multisig contract
+    const constructors = new Constructors(api, aliceKeyringPair);
+    const { address: multisigAddress } = await constructors.new(1, [
+      aliceKeyringPair.address,
+    ]);
+    expect(multisigAddress).to.exist;
+
+    // Bind the contract to the new address
+    const multisig = new Contract(multisigAddress, aliceKeyringPair, api);
+
+    // Create the transaction, the address is ignored for instantiations
+    const instantiateTx: Transaction = {
+      address: multisigAddress,
+      selector: constructorSelector,
+      input: [],
+      transferredValue: 0,
+      refTimeLimit: 0,
+      allowReentry: false,
+      proofSizeLimit: 0,
+      storageDepositLimit: null,
+      callType: {
+        instantiate: {
+          codeHash,
+          salt: Array.from(Buffer.from(multisigAddress)),
+        },
+      },
+    };
+
+    //Listen for the events
+    let newTxExecutedEvent;
+    multisig.events.subscribeOnTransactionExecutedEvent((event) => {
+      newTxExecutedEvent = event;
+    });
+    let contractInstantiatedEvent;
+    multisig.events.subscribeOnContractInstantiatedEvent((event) => {
+      contractInstantiatedEvent = event;
+    });
+
+    // Propose the transaction on chain, it is executed because the threshold is 1
+    await multisig.tx.proposeTx(instantiateTx);
+
+    // The new address is recorded in the result and in the event
+    expect(newTxExecutedEvent).to.exist;
+    expect(Object.keys(newTxExecutedEvent.result)).to.include("instantiated");
+    expect(contractInstantiatedEvent).to.exist;
+
+    const instantiatedAddress = newTxExecutedEvent.result.instantiated;
+    expect(contractInstantiatedEvent.contract).to.equal(instantiatedAddress);
+
+    // The contract exists on chain
+    const contractInfo = await api.query.contracts.contractInfoOf(
+      instantiatedAddress
+    );
+    expect(contractInfo.isSome).to.be.true;
+  });
+});
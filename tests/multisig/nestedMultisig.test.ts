@@ -0,0 +1,180 @@
+import { expect } from "chai";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import ContractAbi from "../../artifacts/multisig/multisig.json";
+import { MessageIndex } from "../utils/MessageIndex";
+import { assignKeyringPairs, buildTransaction } from "../utils/testHelpers";
+import Contract from "../../typed_contracts/multisig/contracts/multisig";
+import Constructors from "../../typed_contracts/multisig/constructors/multisig";
+
+let api;
+let keyring;
+let aliceKeyringPair;
+let bobKeyringPair;
+let charlieKeyringPair;
+let multisigMessageIndex;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+describe("Nested Multisig", () => {
+  before(() => {
+    // call function to create keyring pairs
+    [aliceKeyringPair, bobKeyringPair, charlieKeyringPair] = assignKeyringPairs(
+      keyring,
+      3
+    );
+    // Index that allows to get the selector of a message by its label
+    multisigMessageIndex = new MessageIndex(ContractAbi);
+  });
+
+  // Deploys an inner multisig owned by Alice and an outer multisig owned by the inner one and Bob
+  const deployNestedMultisigs = async () => {
+    const constructors = new Constructors(api, aliceKeyringPair);
+    const { address: innerAddress } = await constructors.new(1, [
+      aliceKeyringPair.address,
+    ]);
+    const { address: outerAddress } = await constructors.new(2, [
+      innerAddress,
+      bobKeyringPair.address,
+    ]);
+    expect(innerAddress).to.exist;
+    expect(outerAddress).to.exist;
+
+    const inner = new Contract(innerAddress, aliceKeyringPair, api);
+    const outer = new Contract(outerAddress, bobKeyringPair, api);
+
+    return [innerAddress, inner, outerAddress, outer];
+  };
+
+  it("Should approve an outer transaction that reenters the inner multisig", async () => {
+    const [innerAddress, inner, outerAddress, outer] =
+      await deployNestedMultisigs();
+
+    // The outer multisig is made the pause guardian of the inner one,
+    // so the outer transaction changes the inner storage when it reenters it
+    const setPauseGuardianTx = await buildTransaction(
+      api,
+      innerAddress,
+      "set_pause_guardian",
+      [outerAddress],
+      multisigMessageIndex
+    );
+    await inner.tx.proposeTx(setPauseGuardianTx);
+
+    // Bob proposes an outer transaction calling the inner multisig
+    const pauseInnerTx = await buildTransaction(
+      api,
+      innerAddress,
+      "pause",
+      [],
+      multisigMessageIndex
+    );
+    expect(pauseInnerTx.allowReentry).to.be.true;
+    await outer.tx.proposeTx(pauseInnerTx);
+
+    // The inner multisig discovers the transaction awaiting its vote
+    let pendingOuterTxs = (
+      await inner.query.getPendingOuterTxs(outerAddress)
+    ).value.unwrap().ok;
+    expect(pendingOuterTxs).to.have.lengthOf(1);
+    expect(pendingOuterTxs[0].proposer).to.equal(bobKeyringPair.address);
+
+    //Listen for the event
+    let outerTxExecutedEvent;
+    outer.events.subscribeOnTransactionExecutedEvent((event) => {
+      outerTxExecutedEvent = event;
+    });
+
+    // The inner multisig approves it, so the outer one executes it and reenters the inner one
+    const approveInTx = await buildTransaction(
+      api,
+      innerAddress,
+      "approve_in",
+      [outerAddress, 0],
+      multisigMessageIndex
+    );
+    await inner.tx.proposeTx(approveInTx);
+
+    expect(outerTxExecutedEvent).to.exist;
+    expect(Object.keys(outerTxExecutedEvent.result)).to.include("success");
+
+    // The change made by the reentrant call is not overwritten by the inner execution
+    const isPaused = (await inner.query.isPaused()).value.unwrap();
+    expect(isPaused).to.be.true;
+
+    const outerTx = (await outer.query.getTx(0)).value.ok;
+    expect(outerTx).to.not.exist;
+
+    pendingOuterTxs = (
+      await inner.query.getPendingOuterTxs(outerAddress)
+    ).value.unwrap().ok;
+    expect(pendingOuterTxs).to.have.lengthOf(0);
+  });
+
+  it("Should fail to vote in the outer multisig if reentry is not allowed", async () => {
+    const [innerAddress, inner, outerAddress, outer] =
+      await deployNestedMultisigs();
+
+    // Bob proposes an outer transaction
+    const changeThresholdTx = await buildTransaction(
+      api,
+      outerAddress,
+      "change_threshold",
+      [1],
+      multisigMessageIndex
+    );
+    await outer.tx.proposeTx(changeThresholdTx);
+
+    // The self call to reject_in reenters the inner multisig, so it must allow reentry
+    const rejectInTx = await buildTransaction(
+      api,
+      innerAddress,
+      "reject_in",
+      [outerAddress, 0],
+      multisigMessageIndex
+    );
+
+    //Listen for the event
+    let innerTxExecutedEvent;
+    inner.events.subscribeOnTransactionExecutedEvent((event) => {
+      innerTxExecutedEvent = event;
+    });
+
+    await inner.tx.proposeTx({ ...rejectInTx, allowReentry: false });
+
+    expect(innerTxExecutedEvent).to.exist;
+    expect(Object.keys(innerTxExecutedEvent.result)).to.include("failed");
+
+    // The outer transaction has not been voted
+    const rejections = (await outer.query.getTxRejections(0)).value.ok;
+    expect(rejections).to.equal(0);
+
+    // With reentry allowed the inner multisig rejects it,
+    // and the outer transaction is cancelled because its threshold cannot be met
+    await inner.tx.proposeTx(rejectInTx);
+
+    const outerTx = (await outer.query.getTx(0)).value.ok;
+    expect(outerTx).to.not.exist;
+  });
+});
@@ -93,8 +93,11 @@ describe("Test payable message", () => {
       selector: selector!,
       input: [],
       transferredValue: transferAmount,
-      gasLimit: 100000000000,
+      refTimeLimit: 100000000000,
       allowReentry: false,
+      proofSizeLimit: 0,
+      storageDepositLimit: null,
+      callType: { call: null },
     };
 
     // Propose the transaction on chain
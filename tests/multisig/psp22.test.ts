@@ -140,8 +140,11 @@ describe("PSP22 Transfer message", () => {
       selector: Array.from(selector),
       input: convertedArgs,
       transferredValue: 0,
-      gasLimit: 0,
+      refTimeLimit: 0,
       allowReentry: false,
+      proofSizeLimit: 0,
+      storageDepositLimit: null,
+      callType: { call: null },
     };
 
     // Propose the transaction on chain
@@ -0,0 +1,107 @@
+import { expect } from "chai";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import ContractAbi from "../../artifacts/multisig/multisig.json";
+import { MessageIndex } from "../utils/MessageIndex";
+import {
+  assignKeyringPairs,
+  createABCMultiSigAndEnsureState,
+  buildTransaction,
+  proposeTransaction,
+} from "../utils/testHelpers";
+
+let api;
+let keyring;
+let keypairs;
+let multisigMessageIndex;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+describe("Transaction Simulation", () => {
+  before(() => {
+    // call function to create keyring pairs
+    keypairs = assignKeyringPairs(keyring, 3);
+    // Index that allows to get the selector of a message by its label
+    multisigMessageIndex = new MessageIndex(ContractAbi);
+  });
+
+  it("Should simulate a pending transaction without changing the state", async () => {
+    // Create a new contract
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs
+    );
+
+    const changeThresholdTx = await buildTransaction(
+      api,
+      address,
+      "change_threshold",
+      [1],
+      multisigMessageIndex
+    );
+
+    // Propose the transaction on chain
+    await proposeTransaction(multisig, changeThresholdTx);
+
+    // Simulate the transaction before it has enough approvals
+    const simulation = (await multisig.query.simulateTx(0)).value.ok.ok;
+    expect(Object.keys(simulation.result)).to.include("success");
+    expect(simulation.gasConsumed.toNumber()).to.be.greaterThan(0);
+
+    // The threshold has not changed and the transaction is still pending
+    const threshold = (await multisig.query.getThreshold()).value.unwrap();
+    expect(threshold).to.equal(2);
+
+    const tx_0 = (await multisig.query.getTx(0)).value.ok;
+    expect(tx_0).to.exist;
+  });
+
+  it("Should report the failure of a candidate transaction", async () => {
+    // Create a new contract
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs
+    );
+
+    // The threshold cannot be greater than the number of owners
+    const changeThresholdTx = await buildTransaction(
+      api,
+      address,
+      "change_threshold",
+      [4],
+      multisigMessageIndex
+    );
+
+    const simulation = (
+      await multisig.query.simulateTransaction(changeThresholdTx)
+    ).value.ok.ok;
+    expect(Object.keys(simulation.result)).to.include("failed");
+
+    // Nothing has been proposed
+    const nextTxId = (await multisig.query.getNextTxId()).value
+      .unwrap()
+      .toNumber();
+    expect(nextTxId).to.equal(0);
+  });
+});
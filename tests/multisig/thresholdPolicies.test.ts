@@ -0,0 +1,317 @@
+import { expect } from "chai";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import ContractAbi from "../../artifacts/multisig/multisig.json";
+import { MessageIndex } from "../utils/MessageIndex";
+import {
+  assignKeyringPairs,
+  createABCMultiSigAndEnsureState,
+  buildTransaction,
+  proposeTransaction,
+} from "../utils/testHelpers";
+import { Transaction } from "../../typed_contracts/multisig/types-arguments/multisig";
+
+let api;
+let keyring;
+let aliceKeyringPair;
+let bobKeyringPair;
+let charlieKeyringPair;
+let daveKeyringPair;
+let keypairs;
+let multisigMessageIndex;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+// Build the set_threshold_policy self call, its ranges can't be encoded by the message index
+const buildSetThresholdPolicyTransaction = (
+  multisigAddress,
+  address,
+  selector,
+  ranges
+) => {
+  const setPolicySelector = multisigMessageIndex.getMessageInfo(
+    "set_threshold_policy"
+  )?.selector.bytes;
+  const input = Array.from(
+    api
+      .createType("(AccountId, [u8; 4], Vec<(u128, Option<u128>, u8)>)", [
+        address,
+        selector,
+        ranges.map((range) => [range.minValue, range.maxValue, range.threshold]),
+      ])
+      .toU8a() as Uint8Array
+  );
+
+  const tx: Transaction = {
+    address: multisigAddress,
+    selector: setPolicySelector!,
+    input,
+    transferredValue: 0,
+    refTimeLimit: 0,
+    allowReentry: true,
+    proofSizeLimit: 0,
+    storageDepositLimit: null,
+    callType: { call: null },
+  };
+
+  return tx;
+};
+
+// Build the change_admin_threshold self call, its Option argument can't be encoded by the message index
+const buildChangeAdminThresholdTransaction = (multisigAddress, adminThreshold) => {
+  const selector = multisigMessageIndex.getMessageInfo(
+    "change_admin_threshold"
+  )?.selector.bytes;
+  const input = Array.from(
+    api.createType("Option<u8>", adminThreshold).toU8a() as Uint8Array
+  );
+
+  const tx: Transaction = {
+    address: multisigAddress,
+    selector: selector!,
+    input,
+    transferredValue: 0,
+    refTimeLimit: 0,
+    allowReentry: true,
+    proofSizeLimit: 0,
+    storageDepositLimit: null,
+    callType: { call: null },
+  };
+
+  return tx;
+};
+
+// Build a call to the policy destination with the given transferred value
+const buildValueTransaction = (address, selector, transferredValue) => {
+  const tx: Transaction = {
+    address,
+    selector,
+    input: [],
+    transferredValue,
+    refTimeLimit: 0,
+    allowReentry: false,
+    proofSizeLimit: 0,
+    storageDepositLimit: null,
+    callType: { call: null },
+  };
+
+  return tx;
+};
+
+describe("Threshold Policies", () => {
+  before(() => {
+    // call function to create keyring pairs
+    keypairs = assignKeyringPairs(keyring, 4);
+    [aliceKeyringPair, bobKeyringPair, charlieKeyringPair, daveKeyringPair] =
+      keypairs;
+    // Index that allows to get the selector of a message by its label
+    multisigMessageIndex = new MessageIndex(ContractAbi);
+  });
+
+  it("Should require the admin threshold for admin self calls once it is set", async () => {
+    // Create a new contract with threshold 1
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs,
+      1
+    );
+
+    // The admin threshold defaults to the threshold
+    let adminThreshold = (
+      await multisig.query.getAdminThreshold()
+    ).value.unwrap();
+    expect(adminThreshold).to.equal(1);
+
+    // A stricter admin threshold is set, executed when proposed because the threshold is 1
+    await multisig.tx.proposeTx(
+      buildChangeAdminThresholdTransaction(address, 2)
+    );
+    adminThreshold = (await multisig.query.getAdminThreshold()).value.unwrap();
+    expect(adminThreshold).to.equal(2);
+
+    const addOwnerTx = await buildTransaction(
+      api,
+      address,
+      "add_owner",
+      [daveKeyringPair.address],
+      multisigMessageIndex
+    );
+
+    // Propose the transaction on chain
+    await proposeTransaction(multisig, addOwnerTx);
+
+    // The transaction is not executed with a single approval
+    const tx_1 = (await multisig.query.getTx(1)).value.ok;
+    expect(tx_1).to.exist;
+
+    const txThreshold = (await multisig.query.getTxThreshold(1)).value.ok;
+    expect(txThreshold).to.equal(2);
+
+    //Listen for the event
+    let newTxExecutedEvent;
+    multisig.events.subscribeOnTransactionExecutedEvent((event) => {
+      newTxExecutedEvent = event;
+    });
+
+    // Approve the transaction by Bob
+    await multisig.withSigner(bobKeyringPair).tx.approveTx(1);
+
+    // Emit the success in the event result
+    expect(newTxExecutedEvent).to.exist;
+    expect(Object.keys(newTxExecutedEvent.result)).to.include("success");
+
+    // Dave is added as a new owner
+    const newOwners = (await multisig.query.getOwners()).value.unwrap();
+    expect(newOwners).to.have.lengthOf(4);
+    expect(newOwners).to.include(daveKeyringPair.address);
+  });
+
+  it("Should not consider transfers as admin self calls", async () => {
+    // Create a new contract with threshold 1
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs,
+      1
+    );
+
+    const transferTx = await buildTransaction(
+      api,
+      address,
+      "transfer",
+      [daveKeyringPair.address, 1000],
+      multisigMessageIndex
+    );
+
+    // There is no policy for the transfer
+    const policy = (
+      await multisig.query.getThresholdPolicy(address, transferTx.selector)
+    ).value.ok;
+    expect(policy).to.not.exist;
+
+    //Listen for the event
+    let newTxExecutedEvent;
+    multisig.events.subscribeOnTransactionExecutedEvent((event) => {
+      newTxExecutedEvent = event;
+    });
+
+    // Propose the transaction on chain
+    await proposeTransaction(multisig, transferTx);
+
+    // Because the threshold is 1, the transfer is executed when proposed
+    expect(newTxExecutedEvent).to.exist;
+    const tx_0 = (await multisig.query.getTx(0)).value.ok;
+    expect(tx_0).to.not.exist;
+  });
+
+  it("Should use the threshold of the policy range containing the transferred value", async () => {
+    // Create a new contract with threshold 2
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs,
+      2
+    );
+    const destination = daveKeyringPair.address;
+    const selector = [0, 0, 0, 0];
+
+    // Set a policy with a bounded range and an unbounded one, given out of order
+    await multisig.tx.proposeTx(
+      buildSetThresholdPolicyTransaction(address, destination, selector, [
+        { minValue: 1000, maxValue: null, threshold: 3 },
+        { minValue: 0, maxValue: 99, threshold: 1 },
+      ])
+    );
+    await multisig.withSigner(bobKeyringPair).tx.approveTx(0);
+
+    // The ranges are kept sorted
+    const policy = (
+      await multisig.query.getThresholdPolicy(destination, selector)
+    ).value.ok;
+    expect(policy).to.have.lengthOf(2);
+    expect(policy[0].minValue.toNumber()).to.equal(0);
+    expect(policy[1].maxValue).to.not.exist;
+
+    //Listen for the event
+    let newTxExecutedEvent;
+    multisig.events.subscribeOnTransactionExecutedEvent((event) => {
+      newTxExecutedEvent = event;
+    });
+
+    // A value within the bounded range meets its threshold of 1 when proposed
+    await multisig.tx.proposeTx(
+      buildValueTransaction(destination, selector, 99)
+    );
+    expect(newTxExecutedEvent).to.exist;
+    expect(newTxExecutedEvent.txId.toNumber()).to.equal(1);
+
+    // A value out of every range uses the default threshold
+    await multisig.tx.proposeTx(
+      buildValueTransaction(destination, selector, 500)
+    );
+    let txThreshold = (await multisig.query.getTxThreshold(2)).value.ok;
+    expect(txThreshold).to.equal(2);
+
+    // The unbounded range matches any value above its minimum
+    await multisig.tx.proposeTx(
+      buildValueTransaction(destination, selector, "1000000000000000000000000")
+    );
+    txThreshold = (await multisig.query.getTxThreshold(3)).value.ok;
+    expect(txThreshold).to.equal(3);
+
+    // Its threshold is not met with two approvals
+    await multisig.withSigner(bobKeyringPair).tx.approveTx(3);
+    let tx_3 = (await multisig.query.getTx(3)).value.ok;
+    expect(tx_3).to.exist;
+
+    // and it can't be met once an owner rejects it, so it is removed
+    await multisig.withSigner(charlieKeyringPair).tx.rejectTx(3);
+    tx_3 = (await multisig.query.getTx(3)).value.ok;
+    expect(tx_3).to.not.exist;
+  });
+
+  it("Should refuse overlapping policy ranges", async () => {
+    // Create a new contract with threshold 2
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs,
+      2
+    );
+    const destination = daveKeyringPair.address;
+    const selector = [0, 0, 0, 0];
+
+    // The ranges share the bound 1000, so a value of 1000 would match both
+    await multisig.tx.proposeTx(
+      buildSetThresholdPolicyTransaction(address, destination, selector, [
+        { minValue: 0, maxValue: 1000, threshold: 1 },
+        { minValue: 1000, maxValue: null, threshold: 3 },
+      ])
+    );
+    await multisig.withSigner(bobKeyringPair).tx.approveTx(0);
+
+    // The policy is not set
+    const policy = (
+      await multisig.query.getThresholdPolicy(destination, selector)
+    ).value.ok;
+    expect(policy).to.not.exist;
+  });
+});
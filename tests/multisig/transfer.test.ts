@@ -85,8 +85,11 @@ describe("Transfer function", () => {
       selector: selector!,
       input: args,
       transferredValue: 0,
-      gasLimit: 100000000000,
+      refTimeLimit: 100000000000,
       allowReentry: true,
+      proofSizeLimit: 0,
+      storageDepositLimit: null,
+      callType: { call: null },
     };
 
     // Get the balance of Bob before the transfer
@@ -150,8 +153,11 @@ describe("Transfer function", () => {
       selector: selector!,
       input: args,
       transferredValue: 0,
-      gasLimit: 100000000000,
+      refTimeLimit: 100000000000,
       allowReentry: true,
+      proofSizeLimit: 0,
+      storageDepositLimit: null,
+      callType: { call: null },
     };
 
     // Execute the transaction on chain
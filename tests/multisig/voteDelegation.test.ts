@@ -0,0 +1,106 @@
+import { expect } from "chai";
+import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
+import ContractAbi from "../../artifacts/multisig/multisig.json";
+import { MessageIndex } from "../utils/MessageIndex";
+import {
+  assignKeyringPairs,
+  createABCMultiSigAndEnsureState,
+  buildTransaction,
+} from "../utils/testHelpers";
+
+let api;
+let keyring;
+let aliceKeyringPair;
+let bobKeyringPair;
+let charlieKeyringPair;
+let daveKeyringPair;
+let keypairs;
+let multisigMessageIndex;
+
+before(async () => {
+  try {
+    // Perform async operations to obtain the api instance
+    const wsProvider = new WsProvider("ws://127.0.0.1:9944");
+
+    api = await ApiPromise.create({ provider: wsProvider });
+
+    if (!wsProvider.isConnected) {
+      throw new Error("Unable to connect to WebSocket");
+    }
+
+    // Create a keyring instance
+    keyring = new Keyring({ type: "sr25519" });
+  } catch (error) {
+    console.error(error);
+    process.exit(1); // Terminate the execution
+  }
+});
+
+after(() => {
+  // Disconnect from the API on completion
+  api.disconnect();
+});
+
+describe("Vote Delegation", () => {
+  before(() => {
+    // call function to create keyring pairs
+    keypairs = assignKeyringPairs(keyring, 4);
+    [aliceKeyringPair, bobKeyringPair, charlieKeyringPair, daveKeyringPair] =
+      keypairs;
+    // Index that allows to get the selector of a message by its label
+    multisigMessageIndex = new MessageIndex(ContractAbi);
+  });
+
+  it("Should count the delegate votes as the owner votes", async () => {
+    // Create a new contract with threshold 3
+    const [address, multisig] = await createABCMultiSigAndEnsureState(
+      api,
+      keypairs,
+      3
+    );
+
+    // Bob delegates his votes to Dave for the next 100 blocks
+    const currentBlock = (await api.query.system.number()).toNumber();
+    await multisig
+      .withSigner(bobKeyringPair)
+      .tx.delegateVotes(daveKeyringPair.address, currentBlock + 100);
+
+    const delegator = (
+      await multisig.query.getDelegator(daveKeyringPair.address)
+    ).value.unwrap();
+    expect(delegator).to.equal(bobKeyringPair.address);
+
+    // Alice proposes a transaction
+    const changeThresholdTx = await buildTransaction(
+      api,
+      address,
+      "change_threshold",
+      [2],
+      multisigMessageIndex
+    );
+    await multisig.tx.proposeTx(changeThresholdTx);
+
+    // Dave approves on behalf of Bob
+    await multisig.withSigner(daveKeyringPair).tx.approveTx(0);
+
+    const txStatus = (await multisig.query.getTxStatus(0)).value.unwrap();
+    expect(txStatus.approvals).to.equal(2);
+    expect(txStatus.delegatedVotes).to.deep.equal([
+      [bobKeyringPair.address, daveKeyringPair.address],
+    ]);
+
+    // Bob cannot vote again
+    const approveResult = (
+      await multisig.withSigner(bobKeyringPair).query.approveTx(0)
+    ).value.ok;
+    expect(Object.keys(approveResult.err)).to.include("alreadyVoted");
+
+    // Once the delegation is revoked Dave cannot vote anymore
+    await multisig.withSigner(bobKeyringPair).tx.revokeDelegation();
+
+    const rejectResult = (
+      await multisig.withSigner(daveKeyringPair).query.rejectTx(0)
+    ).value.ok;
+    expect(Object.keys(rejectResult.err)).to.include("notOwner");
+  });
+});
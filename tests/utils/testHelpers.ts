@@ -88,8 +88,11 @@ export const buildTransaction = async (
     selector: selector!,
     input: args,
     transferredValue: 0,
-    gasLimit: 0,
+    refTimeLimit: 0,
     allowReentry: isReentrancyCall,
+    proofSizeLimit: 0,
+    storageDepositLimit: null,
+    callType: { call: null },
   };
 
   return tx;
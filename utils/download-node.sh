@@ -1,8 +1,8 @@
 #!/bin/bash
 
 # Define the download URLs for different architectures
-linux_url="https://github.com/paritytech/substrate-contracts-node/releases/download/v0.27.0/substrate-contracts-node-linux.tar.gz"
-mac_url="https://github.com/paritytech/substrate-contracts-node/releases/download/v0.27.0/substrate-contracts-node-mac-universal.tar.gz"
+linux_url="https://github.com/paritytech/substrate-contracts-node/releases/download/v0.41.0/substrate-contracts-node-linux.tar.gz"
+mac_url="https://github.com/paritytech/substrate-contracts-node/releases/download/v0.41.0/substrate-contracts-node-mac-universal.tar.gz"
 
 # Define the destination folder
 destination_folder="./contracts_node"